@@ -1,5 +1,11 @@
 extern crate embed_resource;
+use std::collections::BTreeMap;
 use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const INDEXED_FOLDERS_FILE: &str = "src/file_system_interaction/asset_loading/indexed_folders.rs";
+include!("src/file_system_interaction/asset_loading/indexed_folders.rs");
 
 fn main() {
     let target = env::var("TARGET").expect("Failed to read env var TARGET");
@@ -7,4 +13,66 @@ fn main() {
         // on windows we will set our game icon as icon for the executable
         embed_resource::compile("build/windows/icon.rc");
     }
+    generate_asset_index();
+}
+
+/// Writes the files in the indexed asset folders to `asset_index.ron` in the output directory,
+/// because the web build cannot list folders at runtime.
+fn generate_asset_index() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=build/windows");
+    println!("cargo:rerun-if-changed={INDEXED_FOLDERS_FILE}");
+    let assets = Path::new("assets");
+    let mut index = BTreeMap::new();
+    for folder in INDEXED_FOLDERS {
+        let dir = assets.join(folder);
+        println!("cargo:rerun-if-changed={}", dir.display());
+        let mut files = Vec::new();
+        collect_files(&dir, &mut files);
+        let mut files: Vec<_> = files
+            .iter()
+            .map(|path| {
+                let relative = path
+                    .strip_prefix(assets)
+                    .expect("Failed to get path relative to assets");
+                let components: Vec<_> = relative
+                    .components()
+                    .map(|component| {
+                        component
+                            .as_os_str()
+                            .to_str()
+                            .expect("Failed to convert asset path to string")
+                    })
+                    .collect();
+                components.join("/")
+            })
+            .collect();
+        files.sort();
+        index.insert(*folder, files);
+    }
+
+    let entries: Vec<_> = index
+        .iter()
+        .map(|(folder, files)| format!("    {folder:?}: {files:?},\n"))
+        .collect();
+    let out_dir = PathBuf::from(env::var("OUT_DIR").expect("Failed to read env var OUT_DIR"));
+    fs::write(
+        out_dir.join("asset_index.ron"),
+        format!("{{\n{}}}\n", entries.concat()),
+    )
+    .expect("Failed to write asset index");
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries {
+        let path = entry.expect("Failed to read asset directory entry").path();
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
 }
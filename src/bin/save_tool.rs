@@ -0,0 +1,328 @@
+//! Inspects, migrates and converts save files and levels without starting the game.
+//!
+//! Usage:
+//! - `save_tool inspect <file>`: validates a save (`.sav.ron` or `.sav`) or level (`.lvl.ron`) and prints a summary.
+//!   Prefabs used by a level are looked up in the `prefabs` directory next to the level's directory.
+//! - `save_tool validate <level>...`: checks levels for missing dialogs and characters, unknown objects, objects outside of the
+//!   world bounds and broken transitions. Exits with an error if any level has problems, e.g. for pre-commit hooks.
+//! - `save_tool migrate <file>`: upgrades a save to the current version in place.
+//! - `save_tool convert <file> <ron|binary>`: rewrites a save in another format.
+
+use anyhow::{bail, Context, Result};
+use bevy::utils::{HashMap, HashSet};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use the_motion_in_everything::file_system_interaction::game_state_serialization::{
+    is_save_sealed, read_save_version, seal_save, unseal_save, SaveFormat, SaveMigrations,
+    SaveModel, CURRENT_SAVE_VERSION,
+};
+use the_motion_in_everything::file_system_interaction::level_serialization::{
+    format_level_problems, get_level_file_name, ExpandedLevel, LevelValidator, Prefab,
+    SerializedLevel,
+};
+use the_motion_in_everything::file_system_interaction::storage::{FileStorage, Storage};
+use the_motion_in_everything::level_instantiation::spawning::builtin_object_registry;
+use the_motion_in_everything::movement::navigation::nav_mesh_settings;
+
+const USAGE: &str = "Usage:
+    save_tool inspect <file>
+    save_tool validate <level>...
+    save_tool migrate <file>
+    save_tool convert <file> <ron|binary>";
+
+fn main() -> Result<()> {
+    let args: Vec<_> = env::args().skip(1).collect();
+    let args: Vec<_> = args.iter().map(String::as_str).collect();
+    // Levels and saves can only be parsed once the properties of the objects in them are registered
+    builtin_object_registry();
+    match args.as_slice() {
+        ["inspect", file] if file.ends_with(".lvl.ron") => inspect_level(Path::new(file)),
+        ["inspect", file] => inspect_save(Path::new(file)),
+        ["validate", levels @ ..] if !levels.is_empty() => validate_levels(levels),
+        ["migrate", file] => migrate_save(Path::new(file)),
+        ["convert", file, format] => convert_save(Path::new(file), parse_format(format)?),
+        _ => bail!("{USAGE}"),
+    }
+}
+
+fn parse_format(format: &str) -> Result<SaveFormat> {
+    match format {
+        "ron" => Ok(SaveFormat::Ron),
+        "binary" => Ok(SaveFormat::Binary),
+        _ => bail!("Unknown format \"{format}\", expected \"ron\" or \"binary\""),
+    }
+}
+
+/// A save file as found on disk, before and after migrating it.
+struct LoadedSave {
+    format: SaveFormat,
+    sealed: bool,
+    version: u32,
+    save_model: SaveModel,
+}
+
+fn load_save(path: &Path) -> Result<LoadedSave> {
+    let data = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let body = unseal_save(&data)?;
+    let save_model = SaveMigrations::default().read(body)?;
+    Ok(LoadedSave {
+        format: SaveFormat::detect(body),
+        sealed: is_save_sealed(&data),
+        version: read_save_version(body)?,
+        save_model,
+    })
+}
+
+/// Writes a save next to `path`, replacing it. The extension is chosen by `format`.
+fn write_save(path: &Path, save_model: &SaveModel, format: SaveFormat) -> Result<()> {
+    let dir = path.parent().context("Failed to get directory of save")?;
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .context("Save file name is not valid UTF-8")?;
+    let slot = [SaveFormat::Ron, SaveFormat::Binary]
+        .iter()
+        .find_map(|format| file_name.strip_suffix(format.extension()))
+        .context("Save file has neither a .sav.ron nor a .sav extension")?;
+    let new_file_name = format!("{slot}{}", format.extension());
+
+    let storage = FileStorage::new(dir);
+    storage.write(&new_file_name, &seal_save(&format.serialize(save_model)?))?;
+    if new_file_name != file_name {
+        storage.delete(file_name)?;
+    }
+    println!("Wrote {}", dir.join(new_file_name).display());
+    Ok(())
+}
+
+fn inspect_save(path: &Path) -> Result<()> {
+    let LoadedSave {
+        format,
+        sealed,
+        version,
+        save_model,
+    } = load_save(path)?;
+    let metadata = &save_model.metadata;
+
+    println!("Save: {}", path.display());
+    println!("Format: {format:?}");
+    println!("Checksum: {}", if sealed { "valid" } else { "missing" });
+    if version == CURRENT_SAVE_VERSION {
+        println!("Version: {version}");
+    } else {
+        println!("Version: {version} (migrates to {CURRENT_SAVE_VERSION})");
+    }
+    if !metadata.label.is_empty() {
+        println!("Label: {}", metadata.label);
+    }
+    println!("Playtime: {}s", metadata.playtime.as_secs());
+    if let Some(timestamp) = metadata.timestamp {
+        println!("Saved at: {timestamp}");
+    }
+    if let Some(objective) = &metadata.objective {
+        println!("Objective: {objective}");
+    }
+    println!("Level: {}", save_model.scene);
+    println!(
+        "Player position: {}",
+        save_model.player_transform.translation
+    );
+
+    let mut conditions: Vec<_> = save_model
+        .conditions
+        .0
+        .iter()
+        .map(|condition| condition.0.as_str())
+        .collect();
+    conditions.sort();
+    println!(
+        "Conditions ({}): {}",
+        conditions.len(),
+        conditions.join(", ")
+    );
+
+    match &save_model.dialog_event {
+        Some(dialog) => println!(
+            "Dialog: \"{}\" on page {} with {:?}",
+            dialog.dialog.0,
+            dialog
+                .page
+                .as_ref()
+                .map(|page| page.0.as_str())
+                .unwrap_or("<start>"),
+            dialog.source
+        ),
+        None => println!("Dialog: none"),
+    }
+
+    let world = &save_model.world;
+    let mut added: BTreeMap<_, usize> = BTreeMap::new();
+    for object in world.added.iter() {
+        *added.entry(object.object.to_string()).or_default() += 1;
+    }
+    println!(
+        "World: {} removed, {} changed, {} added{}",
+        world.removed.len(),
+        world.changed.len(),
+        world.added.len(),
+        format_counts(&added)
+    );
+    Ok(())
+}
+
+fn inspect_level(path: &Path) -> Result<()> {
+    let level = read_level(path)?;
+    let prefabs = read_prefabs(path)?;
+    let expanded = ExpandedLevel::expand(&level, |name| prefabs.get(name))?;
+
+    let mut counts: BTreeMap<_, usize> = BTreeMap::new();
+    let mut ids = HashSet::new();
+    let mut duplicate_ids = Vec::new();
+    for entry in expanded.objects.iter() {
+        *counts.entry(entry.object.to_string()).or_default() += 1;
+        if !ids.insert(entry.id) {
+            duplicate_ids.push(entry.id);
+        }
+    }
+
+    println!("Level: {}", path.display());
+    println!(
+        "Objects: {}{}",
+        expanded.objects.len(),
+        format_counts(&counts)
+    );
+    if !level.prefabs.is_empty() {
+        println!(
+            "Prefab instances: {} ({} objects)",
+            level.prefabs.len(),
+            expanded.instanced.len()
+        );
+    }
+    if !duplicate_ids.is_empty() {
+        bail!("Level contains duplicate IDs: {duplicate_ids:?}");
+    }
+    Ok(())
+}
+
+fn validate_levels(paths: &[&str]) -> Result<()> {
+    let registry = builtin_object_registry();
+    let mut invalid_levels = 0;
+    for path in paths.iter().map(Path::new) {
+        let level = read_level(path)?;
+        let nav_mesh_settings = nav_mesh_settings(level.streaming.as_ref());
+        let prefabs = read_prefabs(path)?;
+        let expand =
+            |level: &SerializedLevel| ExpandedLevel::expand(level, |name| prefabs.get(name));
+        let dialogs = list_asset_names(path, "dialogs", ".dlg.ron")?;
+        let characters = list_asset_names(path, "characters", ".char.ron")?;
+        let levels_dir = path.parent().context("Failed to get directory of level")?;
+        let validator = LevelValidator {
+            registry: &registry,
+            dialog_exists: &|dialog| dialogs.contains_key(&dialog.0),
+            character_exists: &|character| characters.contains_key(character),
+            get_level: &|name| {
+                let level = read_level(&levels_dir.join(get_level_file_name(name))).ok()?;
+                expand(&level).ok()
+            },
+            world_half_extents: nav_mesh_settings.world_half_extents,
+            world_bottom_bound: nav_mesh_settings.world_bottom_bound,
+        };
+        let problems = validator.validate(&expand(&level)?);
+        if problems.is_empty() {
+            println!("{}: OK", path.display());
+        } else {
+            invalid_levels += 1;
+            println!(
+                "{}: {} problems{}",
+                path.display(),
+                problems.len(),
+                format_level_problems(&problems)
+            );
+        }
+    }
+    if invalid_levels > 0 {
+        bail!("{invalid_levels} of {} levels have problems", paths.len());
+    }
+    Ok(())
+}
+
+fn read_level(path: &Path) -> Result<SerializedLevel> {
+    let serialized =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    ron::from_str(&serialized).with_context(|| format!("Failed to parse level {}", path.display()))
+}
+
+/// Reads all prefabs from the `prefabs` directory that sits next to the directory of `level_path`.
+fn read_prefabs(level_path: &Path) -> Result<HashMap<String, SerializedLevel>> {
+    let mut prefabs = HashMap::new();
+    for (name, path) in list_asset_names(level_path, "prefabs", ".pfb.ron")? {
+        let serialized = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let prefab: Prefab = ron::from_str(&serialized)
+            .with_context(|| format!("Failed to parse prefab {}", path.display()))?;
+        prefabs.insert(name, prefab.0);
+    }
+    Ok(prefabs)
+}
+
+/// Finds the files ending in `suffix` in the directory `subdir` that sits next to the directory of `level_path`,
+/// keyed by their name without the suffix.
+fn list_asset_names(
+    level_path: &Path,
+    subdir: &str,
+    suffix: &str,
+) -> Result<HashMap<String, PathBuf>> {
+    let Some(dir) = level_path
+        .parent()
+        .and_then(Path::parent)
+        .map(|assets| assets.join(subdir))
+        .filter(|dir| dir.is_dir()) else {
+        return Ok(HashMap::new());
+    };
+    let mut names = HashMap::new();
+    for entry in fs::read_dir(&dir).with_context(|| format!("Failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        let Some(name) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(suffix)) else {
+            continue;
+        };
+        names.insert(name.to_owned(), path);
+    }
+    Ok(names)
+}
+
+fn migrate_save(path: &Path) -> Result<()> {
+    let loaded = load_save(path)?;
+    if loaded.version == CURRENT_SAVE_VERSION && loaded.sealed {
+        println!("Save is already at version {CURRENT_SAVE_VERSION}");
+        return Ok(());
+    }
+    write_save(path, &loaded.save_model, loaded.format)?;
+    println!(
+        "Migrated save from version {} to {CURRENT_SAVE_VERSION}",
+        loaded.version
+    );
+    Ok(())
+}
+
+fn convert_save(path: &Path, format: SaveFormat) -> Result<()> {
+    let loaded = load_save(path)?;
+    write_save(path, &loaded.save_model, format)?;
+    println!("Converted save from {:?} to {format:?}", loaded.format);
+    Ok(())
+}
+
+fn format_counts(counts: &BTreeMap<String, usize>) -> String {
+    if counts.is_empty() {
+        return String::new();
+    }
+    let counts: Vec<_> = counts
+        .iter()
+        .map(|(name, count)| format!("{name}: {count}"))
+        .collect();
+    format!(" ({})", counts.join(", "))
+}
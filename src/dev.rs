@@ -1,3 +1,4 @@
+use crate::dev::config_editor::config_editor_plugin;
 use crate::dev::dev_editor::dev_editor_plugin;
 use bevy::diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin};
 use bevy::prelude::*;
@@ -6,6 +7,7 @@ use bevy_prototype_debug_lines::DebugLinesPlugin;
 use bevy_rapier3d::prelude::*;
 use seldom_fn_plugin::FnPluginExt;
 
+pub mod config_editor;
 pub mod dev_editor;
 
 /// Plugin with debugging utility intended for use during development only.
@@ -17,6 +19,7 @@ pub fn dev_plugin(app: &mut App) {
             .add_plugin(FrameTimeDiagnosticsPlugin::default())
             .add_plugin(DebugLinesPlugin::default())
             .fn_plugin(dev_editor_plugin)
+            .fn_plugin(config_editor_plugin)
             .add_plugin(LogDiagnosticsPlugin::filtered(vec![]))
             .add_plugin(RapierDebugRenderPlugin {
                 enabled: false,
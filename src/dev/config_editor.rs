@@ -0,0 +1,359 @@
+use crate::file_system_interaction::asset_loading::ConfigAssets;
+use crate::file_system_interaction::config::GameConfig;
+use crate::file_system_interaction::storage::{FileStorage, Storage};
+use anyhow::{Context, Result};
+use bevy::prelude::*;
+use bevy::reflect::{ReflectMut, ReflectRef, Struct};
+use bevy::utils::HashMap;
+use bevy_editor_pls::editor_window::{EditorWindow, EditorWindowContext};
+use bevy_editor_pls::AddEditorWindow;
+use bevy_egui::egui;
+use bevy_egui::egui::ScrollArea;
+use std::fmt;
+use std::ops::RangeInclusive;
+use toml_edit::{Document, Item, Value};
+
+/// Relative to the working directory, like the levels written by
+/// [`StorageBackend::FileSystem`](crate::file_system_interaction::storage::StorageBackend::FileSystem).
+const CONFIG_DIRECTORY: &str = "assets/config";
+const CONFIG_FILE: &str = "config.game.toml";
+
+pub fn config_editor_plugin(app: &mut App) {
+    app.add_editor_window::<ConfigEditorWindow>();
+}
+
+/// Tunes the [`GameConfig`] while the game runs and writes the result back to its file.
+/// Edits the loaded config asset, so the [`UserSettings`](crate::file_system_interaction::user_settings::UserSettings)
+/// and validation are still applied on top of it.
+pub struct ConfigEditorWindow;
+
+impl EditorWindow for ConfigEditorWindow {
+    type State = ConfigEditorState;
+    const NAME: &'static str = "Game Config";
+    const DEFAULT_SIZE: (f32, f32) = (300., 450.);
+    fn ui(world: &mut World, mut cx: EditorWindowContext, ui: &mut egui::Ui) {
+        let state = cx
+            .state_mut::<ConfigEditorWindow>()
+            .expect("Failed to get config window state");
+
+        let handle = world
+            .get_resource::<ConfigAssets>()
+            .map(|config_assets| config_assets.game.clone());
+        let config = handle
+            .as_ref()
+            .and_then(|handle| world.resource::<Assets<GameConfig>>().get(handle).cloned());
+        let (Some(handle), Some(mut config)) = (handle, config) else {
+            ui.label("The config is not loaded yet.");
+            return;
+        };
+
+        if state.on_disk.is_none() && state.error.is_none() {
+            state.reload();
+        }
+        if let Some(error) = &state.error {
+            ui.colored_label(egui::Color32::RED, error.as_str());
+            if ui.button("Retry").clicked() {
+                state.reload();
+            }
+            return;
+        }
+        let Some(on_disk) = state.on_disk.clone() else {
+            return;
+        };
+
+        let on_disk_values: HashMap<_, _> = config_values(&on_disk.config).into_iter().collect();
+        let mut changed = false;
+        ScrollArea::vertical()
+            .id_source("config-fields")
+            .max_height(300.)
+            .show(ui, |ui| {
+                changed = show_fields(ui, &mut config, "", &on_disk_values);
+            });
+        if changed {
+            set_config(world, &handle, config.clone());
+        }
+        ui.separator();
+
+        ui.heading("Changes");
+        let changes = config_changes(&on_disk.config, &config);
+        if changes.is_empty() {
+            ui.label(format!("Same as {CONFIG_FILE}"));
+        } else {
+            ScrollArea::vertical()
+                .id_source("config-changes")
+                .max_height(100.)
+                .show(ui, |ui| {
+                    for change in changes.iter() {
+                        ui.monospace(change.to_string());
+                    }
+                });
+        }
+        ui.horizontal(|ui| {
+            ui.add_enabled_ui(!changes.is_empty(), |ui| {
+                if ui.button(format!("Write back to {CONFIG_FILE}")).clicked() {
+                    match write_back(&on_disk, &changes) {
+                        Ok(()) => {
+                            info!("Wrote {} changes to {CONFIG_FILE}", changes.len());
+                            state.reload();
+                        }
+                        Err(e) => error!("Failed to write back the game config: {e:#}"),
+                    }
+                }
+            });
+            if ui.button("Revert").clicked() {
+                state.reload();
+                if let Some(on_disk) = &state.on_disk {
+                    set_config(world, &handle, on_disk.config.clone());
+                }
+            }
+        });
+    }
+}
+
+/// Replaces the config asset, which triggers reapplying it like a hot reload would.
+fn set_config(world: &mut World, handle: &Handle<GameConfig>, config: GameConfig) {
+    if let Some(asset) = world.resource_mut::<Assets<GameConfig>>().get_mut(handle) {
+        *asset = config;
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ConfigEditorState {
+    /// Read when the window is first shown and after writing back or reverting.
+    on_disk: Option<OnDiskConfig>,
+    error: Option<String>,
+}
+
+impl ConfigEditorState {
+    fn reload(&mut self) {
+        match OnDiskConfig::read() {
+            Ok(on_disk) => {
+                self.on_disk = Some(on_disk);
+                self.error = None;
+            }
+            Err(e) => {
+                self.on_disk = None;
+                self.error = Some(format!("Failed to read {CONFIG_FILE}: {e:#}"));
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct OnDiskConfig {
+    document: String,
+    config: GameConfig,
+}
+
+impl OnDiskConfig {
+    fn read() -> Result<Self> {
+        let document = config_storage().read(CONFIG_FILE)?;
+        let document = String::from_utf8(document).context("Config file is not valid UTF-8")?;
+        let config = toml_edit::de::from_str(&document).context("Failed to parse config file")?;
+        Ok(Self { document, config })
+    }
+}
+
+fn config_storage() -> FileStorage {
+    FileStorage::new(CONFIG_DIRECTORY)
+}
+
+/// A number in the [`GameConfig`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ConfigValue {
+    Float(f32),
+    Integer(usize),
+}
+
+impl ConfigValue {
+    fn read(value: &dyn Reflect) -> Option<Self> {
+        if let Some(value) = value.downcast_ref::<f32>() {
+            Some(Self::Float(*value))
+        } else {
+            value
+                .downcast_ref::<usize>()
+                .map(|value| Self::Integer(*value))
+        }
+    }
+
+    fn to_toml(self) -> Result<Value> {
+        Ok(match self {
+            // Unlike converting to f64, this doesn't add digits, and whole numbers keep their decimal point
+            Self::Float(value) => format!("{value:?}")
+                .parse()
+                .with_context(|| format!("Failed to convert {value} to TOML"))?,
+            Self::Integer(value) => Value::from(value as i64),
+        })
+    }
+}
+
+impl fmt::Display for ConfigValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Float(value) => write!(f, "{value:?}"),
+            Self::Integer(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+/// Every number in `config`, keyed by its dotted path in the config file, e.g. `camera.fixed_angle.pitch`.
+/// In the order of the struct fields.
+fn config_values(config: &GameConfig) -> Vec<(String, ConfigValue)> {
+    let mut values = Vec::new();
+    collect_values(config, "", &mut values);
+    values
+}
+
+fn collect_values(fields: &dyn Struct, path: &str, values: &mut Vec<(String, ConfigValue)>) {
+    for (index, field) in fields.iter_fields().enumerate() {
+        let Some(name) = fields.name_at(index) else {
+            continue;
+        };
+        let path = join_path(path, name);
+        if let ReflectRef::Struct(nested) = field.reflect_ref() {
+            collect_values(nested, &path, values);
+        } else if let Some(value) = ConfigValue::read(field) {
+            values.push((path, value));
+        }
+    }
+}
+
+fn join_path(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_owned()
+    } else {
+        format!("{path}.{name}")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ConfigChange {
+    path: String,
+    on_disk: ConfigValue,
+    current: ConfigValue,
+}
+
+impl fmt::Display for ConfigChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} -> {}", self.path, self.on_disk, self.current)
+    }
+}
+
+fn config_changes(on_disk: &GameConfig, current: &GameConfig) -> Vec<ConfigChange> {
+    config_values(on_disk)
+        .into_iter()
+        .zip(config_values(current))
+        .filter(|((_, on_disk), (_, current))| on_disk != current)
+        .map(|((path, on_disk), (_, current))| ConfigChange {
+            path,
+            on_disk,
+            current,
+        })
+        .collect()
+}
+
+/// Shows a slider for every number in `fields` and a collapsible section for every nested struct.
+/// Returns whether anything was changed.
+fn show_fields(
+    ui: &mut egui::Ui,
+    fields: &mut dyn Struct,
+    path: &str,
+    on_disk: &HashMap<String, ConfigValue>,
+) -> bool {
+    let mut changed = false;
+    for index in 0..fields.field_len() {
+        let Some(name) = fields.name_at(index).map(str::to_owned) else {
+            continue;
+        };
+        let path = join_path(path, &name);
+        let Some(field) = fields.field_at_mut(index) else {
+            continue;
+        };
+        match field.reflect_mut() {
+            ReflectMut::Struct(nested) => {
+                changed |= egui::CollapsingHeader::new(name.as_str())
+                    .id_source(&path)
+                    .show(ui, |ui| show_fields(ui, nested, &path, on_disk))
+                    .body_returned
+                    .unwrap_or_default();
+            }
+            ReflectMut::Value(value) => {
+                ui.horizontal(|ui| {
+                    ui.label(name.as_str());
+                    changed |= number_slider(ui, value, on_disk.get(&path).copied());
+                });
+            }
+            _ => {
+                ui.label(format!("{name}: not editable"));
+            }
+        }
+    }
+    changed
+}
+
+fn number_slider(ui: &mut egui::Ui, value: &mut dyn Reflect, on_disk: Option<ConfigValue>) -> bool {
+    if let Some(value) = value.downcast_mut::<f32>() {
+        let reference = match on_disk {
+            Some(ConfigValue::Float(on_disk)) => on_disk,
+            _ => *value,
+        };
+        ui.add(egui::Slider::new(value, slider_range(reference)).clamp_to_range(false))
+            .changed()
+    } else if let Some(value) = value.downcast_mut::<usize>() {
+        let reference = match on_disk {
+            Some(ConfigValue::Integer(on_disk)) => on_disk,
+            _ => *value,
+        };
+        ui.add(egui::Slider::new(value, 0..=(reference * 2).max(10)).clamp_to_range(false))
+            .changed()
+    } else {
+        ui.label("not editable");
+        false
+    }
+}
+
+/// Centers the value on disk on the slider, so that it can be tuned in both directions.
+/// Values outside the range can still be typed in.
+fn slider_range(on_disk: f32) -> RangeInclusive<f32> {
+    let extent = if on_disk == 0.0 {
+        1.0
+    } else {
+        on_disk.abs() * 2.0
+    };
+    if on_disk < 0.0 {
+        -extent..=0.0
+    } else {
+        0.0..=extent
+    }
+}
+
+/// Replaces the changed values in the config file, keeping its key order, comments and line endings.
+fn write_back(on_disk: &OnDiskConfig, changes: &[ConfigChange]) -> Result<()> {
+    let mut document: Document = on_disk
+        .document
+        .parse()
+        .context("Failed to parse config file")?;
+    for change in changes {
+        let (table_path, key) = change.path.rsplit_once('.').unwrap_or(("", &change.path));
+        let mut table = document.as_table_mut();
+        for name in table_path.split('.').filter(|name| !name.is_empty()) {
+            table = table
+                .entry(name)
+                .or_insert(toml_edit::table())
+                .as_table_mut()
+                .with_context(|| format!("{table_path} is not a table"))?;
+        }
+        let mut value = change.current.to_toml()?;
+        if let Some(existing) = table.get_mut(key).and_then(Item::as_value_mut) {
+            *value.decor_mut() = existing.decor().clone();
+            *existing = value;
+        } else {
+            table.insert(key, Item::Value(value));
+        }
+    }
+    let mut serialized = document.to_string();
+    if on_disk.document.contains("\r\n") {
+        serialized = serialized.replace("\r\n", "\n").replace('\n', "\r\n");
+    }
+    config_storage().write(CONFIG_FILE, serialized.as_bytes())
+}
@@ -1,6 +1,11 @@
-use crate::file_system_interaction::game_state_serialization::{GameLoadRequest, GameSaveRequest};
-use crate::file_system_interaction::level_serialization::{WorldLoadRequest, WorldSaveRequest};
-use crate::level_instantiation::spawning::GameObject;
+use crate::file_system_interaction::game_state_serialization::{
+    GameLoadRequest, GameSaveRequest, SaveFormat,
+};
+use crate::file_system_interaction::level_serialization::{
+    preview_level_save, WorldLoadRequest, WorldSaveRequest,
+};
+use crate::level_instantiation::spawning::objects::spawn_point::DEFAULT_SPAWN_POINT;
+use crate::level_instantiation::spawning::{GameObject, GameObjectRegistry, SpawnData};
 use crate::player_control::camera::ForceCursorGrabMode;
 use crate::GameState;
 use anyhow::{Context, Result};
@@ -15,8 +20,6 @@ use bevy_prototype_debug_lines::DebugLines;
 use bevy_rapier3d::prelude::*;
 use oxidized_navigation::NavMesh;
 use serde::{Deserialize, Serialize};
-use spew::prelude::*;
-use strum::IntoEnumIterator;
 
 pub fn dev_editor_plugin(app: &mut App) {
     app.init_resource::<DevEditorState>()
@@ -61,50 +64,94 @@ impl EditorWindow for DevEditorWindow {
         ui.add_enabled_ui(!state.level_name.is_empty(), |ui| {
             ui.horizontal(|ui| {
                 if ui.button("Save").clicked() {
-                    world.send_event(WorldSaveRequest {
-                        filename: state.level_name.clone(),
-                    })
+                    match preview_level_save(world, &state.level_name) {
+                        Ok(Some(diff)) => {
+                            state.overwrite_preview = Some(LevelOverwritePreview {
+                                level_name: state.level_name.clone(),
+                                summary: diff.to_string(),
+                                details: diff.describe(),
+                            })
+                        }
+                        Ok(None) => world.send_event(WorldSaveRequest {
+                            filename: state.level_name.clone(),
+                            overwrite: false,
+                        }),
+                        Err(e) => error!("Failed to compare level with the one on disk: {e:#}"),
+                    }
                 }
                 if ui.button("Load").clicked() {
                     world.send_event(WorldLoadRequest {
                         filename: state.level_name.clone(),
+                        spawn_point: Some(DEFAULT_SPAWN_POINT.to_owned()),
                     });
-                    // Make sure the player is spawned after the level
-                    world.send_event(
-                        SpawnEvent::with_data(
-                            GameObject::Player,
-                            Transform::from_translation((0., 1.5, 0.).into()),
-                        )
-                        .delay_frames(2),
-                    );
                 }
             });
         });
+        if let Some(preview) = state.overwrite_preview.clone() {
+            ui.label(format!(
+                "\"{}\" already exists. Overwrite it? {}",
+                preview.level_name, preview.summary
+            ));
+            ScrollArea::vertical()
+                .id_source("level-overwrite-preview")
+                .max_height(100.)
+                .show(ui, |ui| {
+                    for line in preview.details.iter() {
+                        ui.monospace(line);
+                    }
+                });
+            ui.horizontal(|ui| {
+                if ui.button("Overwrite").clicked() {
+                    world.send_event(WorldSaveRequest {
+                        filename: preview.level_name.clone(),
+                        overwrite: true,
+                    });
+                    state.overwrite_preview = None;
+                }
+                if ui.button("Cancel").clicked() {
+                    state.overwrite_preview = None;
+                }
+            });
+        }
+
         ui.horizontal(|ui| {
             ui.label("Save name: ");
             ui.text_edit_singleline(&mut state.save_name);
         });
 
         ui.horizontal(|ui| {
-            let filename = (!state.save_name.is_empty()).then(|| state.save_name.clone());
+            let slot = (!state.save_name.is_empty()).then(|| state.save_name.clone());
             if ui.button("Save").clicked() {
                 world.send_event(GameSaveRequest {
-                    filename: filename.clone(),
+                    slot: slot.clone(),
+                    label: None,
                 })
             }
             if ui.button("Load").clicked() {
-                world.send_event(GameLoadRequest { filename });
+                world.send_event(GameLoadRequest { slot });
             }
         });
+        ui.horizontal(|ui| {
+            ui.label("Save format: ");
+            let mut format = world.resource_mut::<SaveFormat>();
+            ui.radio_value(&mut *format, SaveFormat::Ron, "RON");
+            ui.radio_value(&mut *format, SaveFormat::Binary, "Binary");
+        });
 
         ui.add_space(10.);
         ui.label("Spawning");
-        if ui.button("Spawn").clicked() {
-            world.send_event(SpawnEvent::with_data(
-                state.spawn_item,
-                Transform::default(),
-            ));
-        }
+        let registry = world.resource::<GameObjectRegistry>();
+        let spawn_event =
+            registry.spawn_event(&state.spawn_item, SpawnData::from(Transform::default()));
+        let items: Vec<_> = registry
+            .iter()
+            .map(|registration| registration.object.clone())
+            .collect();
+        ui.add_enabled_ui(spawn_event.is_some(), |ui| {
+            if ui.button("Spawn").clicked() && let Some(spawn_event) = spawn_event {
+                world.send_event(spawn_event);
+            }
+        });
 
         ui.add_space(3.);
 
@@ -112,8 +159,9 @@ impl EditorWindow for DevEditorWindow {
             .auto_shrink([false; 2])
             .show(ui, |ui| {
                 ui.vertical(|ui| {
-                    for item in GameObject::iter() {
-                        ui.radio_value(&mut state.spawn_item, item, format!("{item:?}"));
+                    for item in items {
+                        let label = item.to_string();
+                        ui.radio_value(&mut state.spawn_item, item, label);
                     }
                 });
             });
@@ -129,6 +177,19 @@ pub struct DevEditorState {
     pub spawn_item: GameObject,
     pub collider_render_enabled: bool,
     pub navmesh_render_enabled: bool,
+    /// Shown when saving would replace a level on disk, until the overwrite is confirmed or cancelled.
+    #[serde(skip)]
+    pub overwrite_preview: Option<LevelOverwritePreview>,
+}
+
+/// What overwriting a level on disk would change, see
+/// [`LevelDiff`](crate::file_system_interaction::level_serialization::LevelDiff).
+#[derive(Debug, Clone, Eq, PartialEq, Reflect, FromReflect, Serialize, Deserialize, Default)]
+#[reflect(Serialize, Deserialize)]
+pub struct LevelOverwritePreview {
+    pub level_name: String,
+    pub summary: String,
+    pub details: Vec<String>,
 }
 
 impl Default for DevEditorState {
@@ -140,6 +201,7 @@ impl Default for DevEditorState {
             collider_render_enabled: false,
             navmesh_render_enabled: false,
             open: false,
+            overwrite_preview: None,
         }
     }
 }
@@ -3,6 +3,8 @@ pub mod audio;
 pub mod config;
 pub mod game_state_serialization;
 pub mod level_serialization;
+pub mod storage;
+pub mod user_settings;
 
 use bevy::prelude::*;
 
@@ -10,6 +12,8 @@ use crate::file_system_interaction::asset_loading::loading_plugin;
 use crate::file_system_interaction::audio::internal_audio_plugin;
 use crate::file_system_interaction::game_state_serialization::game_state_serialization_plugin;
 use crate::file_system_interaction::level_serialization::level_serialization_plugin;
+use crate::file_system_interaction::storage::storage_plugin;
+use crate::file_system_interaction::user_settings::user_settings_plugin;
 use seldom_fn_plugin::FnPluginExt;
 
 /// Handles loading and saving of levels and save states to disk.
@@ -17,9 +21,13 @@ use seldom_fn_plugin::FnPluginExt;
 /// - [`loading_plugin`] handles loading of assets.
 /// - [`game_state_serialization_plugin`] handles saving and loading of game states.
 /// - [`level_serialization_plugin`] handles saving and loading of levels.
+/// - [`storage_plugin`] selects where saves and levels are stored.
+/// - [`user_settings_plugin`] stores the player's settings, which override parts of the game config.
 /// - [`internal_audio_plugin`]: Handles audio initialization
 pub fn file_system_interaction_plugin(app: &mut App) {
-    app.fn_plugin(loading_plugin)
+    app.fn_plugin(storage_plugin)
+        .fn_plugin(loading_plugin)
+        .fn_plugin(user_settings_plugin)
         .fn_plugin(game_state_serialization_plugin)
         .fn_plugin(level_serialization_plugin)
         .fn_plugin(internal_audio_plugin);
@@ -1,8 +1,9 @@
-use crate::file_system_interaction::config::GameConfig;
-use crate::file_system_interaction::level_serialization::SerializedLevel;
+use crate::file_system_interaction::config::{format_config_problems, GameConfig};
+use crate::file_system_interaction::level_serialization::{Prefab, SerializedLevel};
+use crate::file_system_interaction::user_settings::UserSettings;
+use crate::level_instantiation::spawning::CharacterDefinition;
 use crate::world_interaction::dialog::Dialog;
 use crate::GameState;
-use anyhow::{Context, Result};
 use bevy::prelude::*;
 use bevy::utils::HashMap;
 use bevy_asset_loader::prelude::*;
@@ -10,23 +11,40 @@ use bevy_common_assets::ron::RonAssetPlugin;
 use bevy_common_assets::toml::TomlAssetPlugin;
 use bevy_egui::egui::ProgressBar;
 use bevy_egui::{egui, EguiContexts};
-use bevy_kira_audio::AudioSource;
-use bevy_mod_sysfail::macros::*;
-use iyes_progress::{ProgressCounter, ProgressPlugin};
+use iyes_progress::{ProgressCounter, ProgressPlugin, ProgressSystem};
+
+mod index;
+mod manifest;
+use index::load_indexed_folders;
+pub use index::{AssetIndex, INDEXED_FOLDERS};
+pub use manifest::{keys, AssetKind, AssetManifest, ManifestAssets, NamedAssets};
+use manifest::{load_named_assets, report_failed_assets};
 
 pub fn loading_plugin(app: &mut App) {
     app.add_plugin(RonAssetPlugin::<SerializedLevel>::new(&["lvl.ron"]))
+        .add_plugin(RonAssetPlugin::<Prefab>::new(&["pfb.ron"]))
         .add_plugin(RonAssetPlugin::<Dialog>::new(&["dlg.ron"]))
+        .add_plugin(RonAssetPlugin::<CharacterDefinition>::new(&["char.ron"]))
         .add_plugin(TomlAssetPlugin::<GameConfig>::new(&["game.toml"]))
+        .add_plugin(RonAssetPlugin::<AssetManifest>::new(&["manifest.ron"]))
         .add_plugin(ProgressPlugin::new(GameState::Loading).continue_to(GameState::Menu))
         .add_loading_state(LoadingState::new(GameState::Loading).continue_to_state(GameState::Menu))
-        .add_collection_to_loading_state::<_, AudioAssets>(GameState::Loading)
-        .add_collection_to_loading_state::<_, SceneAssets>(GameState::Loading)
-        .add_collection_to_loading_state::<_, AnimationAssets>(GameState::Loading)
-        .add_collection_to_loading_state::<_, LevelAssets>(GameState::Loading)
-        .add_collection_to_loading_state::<_, DialogAssets>(GameState::Loading)
-        .add_collection_to_loading_state::<_, TextureAssets>(GameState::Loading)
+        .add_collection_to_loading_state::<_, ManifestAssets>(GameState::Loading)
         .add_collection_to_loading_state::<_, ConfigAssets>(GameState::Loading)
+        .insert_resource(AssetIndex::discover())
+        .init_resource::<LevelAssets>()
+        .init_resource::<DialogAssets>()
+        .add_system(
+            load_indexed_folders
+                .track_progress()
+                .in_set(OnUpdate(GameState::Loading)),
+        )
+        .add_system(
+            load_named_assets
+                .track_progress()
+                .in_set(OnUpdate(GameState::Loading)),
+        )
+        .add_system(report_failed_assets.in_schedule(OnExit(GameState::Loading)))
         .add_system(show_progress.in_set(OnUpdate(GameState::Loading)))
         .add_system(update_config);
 }
@@ -35,76 +53,44 @@ pub fn loading_plugin(app: &mut App) {
 // when done loading, they will be inserted as resources (see <https://github.com/NiklasEi/bevy_asset_loader>)
 
 #[derive(AssetCollection, Resource, Clone)]
-pub struct AudioAssets {
-    #[asset(path = "audio/walking.ogg")]
-    pub walking: Handle<AudioSource>,
+pub struct ConfigAssets {
+    #[asset(path = "config/config.game.toml")]
+    pub game: Handle<GameConfig>,
 }
 
-// ToDo: update player character
-#[derive(AssetCollection, Resource, Clone)]
-pub struct SceneAssets {
-    #[asset(path = "scenes/Fox.glb#Scene0")]
-    pub character: Handle<Scene>,
-    #[asset(path = "scenes/old_town.glb#Scene0")]
-    pub level: Handle<Scene>,
+/// Every prefab listed in the [`AssetIndex`]. Inserted once all of them are loaded.
+#[derive(Resource, Clone)]
+pub struct PrefabAssets {
+    pub prefabs: HashMap<String, Handle<Prefab>>,
 }
 
-// ToDo: update player character
-#[derive(AssetCollection, Resource, Clone)]
-pub struct AnimationAssets {
-    #[asset(path = "scenes/Fox.glb#Animation0")]
-    pub character_idle: Handle<AnimationClip>,
-    #[asset(path = "scenes/Fox.glb#Animation1")]
-    pub character_walking: Handle<AnimationClip>,
-    #[asset(path = "scenes/Fox.glb#Animation2")]
-    pub character_running: Handle<AnimationClip>,
+/// Every character listed in the [`AssetIndex`]. Inserted once all of them are loaded.
+#[derive(Resource, Clone)]
+pub struct CharacterAssets {
+    pub characters: HashMap<String, Handle<CharacterDefinition>>,
 }
 
-// ToDo: update game world
-#[derive(AssetCollection, Resource, Clone)]
+/// The levels that are loaded, i.e. the current one and the ones its transitions lead to.
+/// Filled when a level is requested, see [`WorldLoadRequest`](crate::file_system_interaction::level_serialization::WorldLoadRequest).
+#[derive(Resource, Clone, Default)]
 pub struct LevelAssets {
-    #[cfg_attr(feature = "native", asset(path = "levels", collection(typed, mapped)))]
-    #[cfg_attr(
-        feature = "wasm",
-        asset(paths("levels/old_town.lvl.ron"), collection(typed, mapped))
-    )]
     pub levels: HashMap<String, Handle<SerializedLevel>>,
 }
 
-#[derive(AssetCollection, Resource, Clone)]
+/// The dialogs used by the current level. Loaded together with it, like [`LevelAssets`].
+#[derive(Resource, Clone, Default)]
 pub struct DialogAssets {
-    #[cfg_attr(feature = "native", asset(path = "dialogs", collection(typed, mapped)))]
-    #[cfg_attr(
-        feature = "wasm",
-        asset(paths("dialogs/follower.dlg.ron"), collection(typed, mapped))
-    )]
     pub dialogs: HashMap<String, Handle<Dialog>>,
 }
 
-#[derive(AssetCollection, Resource, Clone)]
-pub struct TextureAssets {
-    #[asset(path = "textures/stone_alley_2.jpg")]
-    pub glowy_interior: Handle<Image>,
-    #[asset(path = "textures/sky.jpg")]
-    pub sky: Handle<Image>,
-}
-
-#[derive(AssetCollection, Resource, Clone)]
-pub struct ConfigAssets {
-    #[asset(path = "config/config.game.toml")]
-    pub game: Handle<GameConfig>,
-}
-
 fn show_progress(
     progress: Option<Res<ProgressCounter>>,
     mut egui_contexts: EguiContexts,
     mut last_done: Local<u32>,
-    audio_assets: Option<Res<AudioAssets>>,
-    scene_assets: Option<Res<SceneAssets>>,
-    animation_assets: Option<Res<AnimationAssets>>,
-    level_assets: Option<Res<LevelAssets>>,
-    dialog_assets: Option<Res<DialogAssets>>,
-    texture_assets: Option<Res<TextureAssets>>,
+    manifest_assets: Option<Res<ManifestAssets>>,
+    named_assets: Option<Res<NamedAssets>>,
+    prefab_assets: Option<Res<PrefabAssets>>,
+    character_assets: Option<Res<CharacterAssets>>,
     config_assets: Option<Res<ConfigAssets>>,
 ) {
     if let Some(progress) = progress.map(|counter| counter.progress()) {
@@ -122,12 +108,10 @@ fn show_progress(
                 );
                 ui.add_space(100.0);
                 ui.add_enabled_ui(false, |ui| {
-                    ui.checkbox(&mut audio_assets.is_some(), "Audio");
-                    ui.checkbox(&mut scene_assets.is_some(), "Scenes");
-                    ui.checkbox(&mut animation_assets.is_some(), "Animations");
-                    ui.checkbox(&mut level_assets.is_some(), "Levels");
-                    ui.checkbox(&mut dialog_assets.is_some(), "Dialogs");
-                    ui.checkbox(&mut texture_assets.is_some(), "Textures");
+                    ui.checkbox(&mut manifest_assets.is_some(), "Manifest");
+                    ui.checkbox(&mut named_assets.is_some(), "Models, sounds and textures");
+                    ui.checkbox(&mut prefab_assets.is_some(), "Prefabs");
+                    ui.checkbox(&mut character_assets.is_some(), "Characters");
                     ui.checkbox(&mut config_assets.is_some(), "Config");
                 });
             });
@@ -135,25 +119,48 @@ fn show_progress(
     }
 }
 
-#[sysfail(log(level = "error"))]
+/// Inserts the [`GameConfig`] whenever its file or the [`UserSettings`] change.
 fn update_config(
     mut commands: Commands,
-    config: Res<Assets<GameConfig>>,
+    configs: Res<Assets<GameConfig>>,
+    config_assets: Option<Res<ConfigAssets>>,
+    settings: Option<Res<UserSettings>>,
     mut config_asset_events: EventReader<AssetEvent<GameConfig>>,
-) -> Result<()> {
+) {
     #[cfg(feature = "tracing")]
     let _span = info_span!("update_config").entered();
-    for event in config_asset_events.iter() {
-        match event {
+    let modified = config_asset_events
+        .iter()
+        .filter_map(|event| match event {
             AssetEvent::Created { handle } | AssetEvent::Modified { handle } => {
-                // Guaranteed by Bevy to not fail
-                let config = config
-                    .get(handle)
-                    .context("Failed to get config even though it was just created")?;
-                commands.insert_resource(config.clone());
+                Some(handle.clone())
             }
-            AssetEvent::Removed { .. } => {}
-        }
+            AssetEvent::Removed { .. } => None,
+        })
+        .last();
+    let settings_changed = settings
+        .as_ref()
+        .map_or(false, |settings| settings.is_changed());
+    let handle = match (modified, config_assets) {
+        (Some(handle), _) => handle,
+        (None, Some(config_assets)) if settings_changed => config_assets.game.clone(),
+        _ => return,
+    };
+    // Settings can change before the config is loaded, which inserts it anyway
+    let Some(config) = configs.get(&handle) else {
+        return;
+    };
+    let mut config = config.clone();
+    if let Some(settings) = settings {
+        settings.apply(&mut config);
+    }
+    let problems = config.validate();
+    if !problems.is_empty() {
+        error!(
+            "The game config has {} problems:{}",
+            problems.len(),
+            format_config_problems(&problems)
+        );
     }
-    Ok(())
+    commands.insert_resource(config);
 }
@@ -0,0 +1,208 @@
+use crate::file_system_interaction::asset_loading::{CharacterAssets, PrefabAssets};
+use anyhow::{Context, Result};
+use bevy::asset::{Asset, HandleId, LoadState};
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use iyes_progress::Progress;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+include!("indexed_folders.rs");
+
+/// The files in each of the [`INDEXED_FOLDERS`] as paths relative to the asset folder,
+/// e.g. `"levels": ["levels/old_town.lvl.ron"]`.
+/// The web build cannot list folders, so it reads the index generated by `build.rs` instead.
+#[derive(Debug, Clone, PartialEq, Eq, Resource, Serialize, Deserialize, Default)]
+#[serde(transparent)]
+pub struct AssetIndex(pub BTreeMap<String, BTreeSet<String>>);
+
+impl AssetIndex {
+    /// The index of the asset folder at compile time.
+    pub fn generated() -> Result<Self> {
+        let serialized = include_str!(concat!(env!("OUT_DIR"), "/asset_index.ron"));
+        ron::from_str(serialized).context("Failed to parse generated asset index")
+    }
+
+    /// Lists the indexed folders in `assets_dir`.
+    pub fn scan(assets_dir: &Path) -> Result<Self> {
+        fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+            if !dir.is_dir() {
+                return Ok(());
+            }
+            let entries =
+                fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))?;
+            for entry in entries {
+                let path = entry?.path();
+                if path.is_dir() {
+                    collect_files(&path, files)?;
+                } else {
+                    files.push(path);
+                }
+            }
+            Ok(())
+        }
+
+        let mut index = BTreeMap::new();
+        for folder in INDEXED_FOLDERS {
+            let mut files = Vec::new();
+            collect_files(&assets_dir.join(folder), &mut files)?;
+            let files = files
+                .iter()
+                .map(|path| {
+                    let relative = path.strip_prefix(assets_dir)?;
+                    let components: Option<Vec<_>> = relative
+                        .components()
+                        .map(|component| component.as_os_str().to_str())
+                        .collect();
+                    components
+                        .map(|components| components.join("/"))
+                        .with_context(|| format!("Failed to convert path to string: {path:?}"))
+                })
+                .collect::<Result<_>>()?;
+            index.insert(folder.to_string(), files);
+        }
+        Ok(Self(index))
+    }
+
+    /// Native builds look at the asset folder itself, so that assets added after compiling are found too.
+    /// The web build uses the [generated](Self::generated) index.
+    pub fn discover() -> Self {
+        let generated = Self::generated().unwrap_or_else(|e| {
+            error!("{e:#}");
+            default()
+        });
+        #[cfg(feature = "native")]
+        {
+            let assets_dir = bevy::asset::FileAssetIo::get_base_path().join("assets");
+            match Self::scan(&assets_dir) {
+                Ok(scanned) => {
+                    if scanned != generated {
+                        warn!(
+                            "The asset index used by the web build is out of date. Rebuild to update it. Differences: {}",
+                            generated.differences(&scanned).join(", ")
+                        );
+                    }
+                    return scanned;
+                }
+                Err(e) => {
+                    error!("Failed to scan assets, falling back to the generated index: {e:#}")
+                }
+            }
+        }
+        generated
+    }
+
+    /// The files in one of the [`INDEXED_FOLDERS`].
+    pub fn files(&self, folder: &str) -> impl Iterator<Item = &str> {
+        self.0
+            .get(folder)
+            .into_iter()
+            .flat_map(|files| files.iter().map(String::as_str))
+    }
+
+    pub fn contains(&self, path: &str) -> bool {
+        self.0.values().any(|files| files.contains(path))
+    }
+
+    /// Describes the files only one of the indices has, e.g. `+levels/forest.lvl.ron` for a file only `other` has.
+    pub fn differences(&self, other: &Self) -> Vec<String> {
+        let files =
+            |index: &Self| -> BTreeSet<String> { index.0.values().flatten().cloned().collect() };
+        let (own, other) = (files(self), files(other));
+        own.difference(&other)
+            .map(|path| format!("-{path}"))
+            .chain(other.difference(&own).map(|path| format!("+{path}")))
+            .collect()
+    }
+}
+
+/// Handles of indexed folders that are being loaded, inserted as resources once everything is done.
+#[derive(Default)]
+pub(super) struct PendingFolders {
+    prefabs: Option<PrefabAssets>,
+    characters: Option<CharacterAssets>,
+    done: bool,
+}
+
+/// Loads the prefabs and characters listed in the [`AssetIndex`], the same way on native and on the web.
+pub(super) fn load_indexed_folders(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    index: Res<AssetIndex>,
+    mut pending: Local<PendingFolders>,
+) -> Progress {
+    fn load_folder<T: Asset>(
+        index: &AssetIndex,
+        folder: &str,
+        asset_server: &AssetServer,
+    ) -> HashMap<String, Handle<T>> {
+        index
+            .files(folder)
+            .map(|path| (path.to_owned(), asset_server.load(path)))
+            .collect()
+    }
+    fn ids<T: Asset>(handles: &HashMap<String, Handle<T>>) -> Vec<(String, HandleId)> {
+        handles
+            .iter()
+            .map(|(path, handle)| (path.clone(), handle.id()))
+            .collect()
+    }
+    let prefabs = pending.prefabs.get_or_insert_with(|| PrefabAssets {
+        prefabs: load_folder(&index, "prefabs", &asset_server),
+    });
+    let mut handles = ids(&prefabs.prefabs);
+    let characters = pending.characters.get_or_insert_with(|| CharacterAssets {
+        characters: load_folder(&index, "characters", &asset_server),
+    });
+    handles.extend(ids(&characters.characters));
+
+    let total = handles.len() as u32;
+    if pending.done {
+        return Progress { done: total, total };
+    }
+    let is_done = |id: HandleId| {
+        matches!(
+            asset_server.get_load_state(id),
+            LoadState::Loaded | LoadState::Failed
+        )
+    };
+    let done = handles.iter().filter(|(_, id)| is_done(*id)).count() as u32;
+    if done == total {
+        for (path, id) in handles.iter() {
+            if asset_server.get_load_state(*id) == LoadState::Failed {
+                error!("Failed to load {path}");
+            }
+        }
+        pending.done = true;
+        if let Some(prefabs) = pending.prefabs.clone() {
+            commands.insert_resource(prefabs);
+        }
+        if let Some(characters) = pending.characters.clone() {
+            commands.insert_resource(characters);
+        }
+    }
+    Progress { done, total }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Native builds scan the asset folder, the web build uses the generated index.
+    /// Both must see the same levels, dialogs, prefabs and characters.
+    #[test]
+    fn generated_index_matches_asset_folder() {
+        let scanned = AssetIndex::scan(Path::new("assets")).unwrap();
+        let generated = AssetIndex::generated().unwrap();
+        assert_eq!(
+            scanned,
+            generated,
+            "Differences: {}",
+            generated.differences(&scanned).join(", ")
+        );
+        assert!(scanned.files("levels").next().is_some());
+        assert!(scanned.files("dialogs").next().is_some());
+    }
+}
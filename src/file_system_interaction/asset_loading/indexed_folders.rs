@@ -0,0 +1,4 @@
+// Shared between `build.rs` and the game through `include!`, so that both index the same folders.
+
+/// The asset folders whose contents are discovered at runtime.
+pub const INDEXED_FOLDERS: &[&str] = &["levels", "dialogs", "prefabs", "characters"];
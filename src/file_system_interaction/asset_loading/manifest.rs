@@ -0,0 +1,271 @@
+use crate::file_system_interaction::asset_loading::CharacterAssets;
+use crate::level_instantiation::spawning::CharacterDefinition;
+use bevy::asset::{Asset, HandleId, LoadState};
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy::utils::{HashMap, HashSet};
+use bevy_asset_loader::prelude::*;
+use bevy_kira_audio::AudioSource;
+use iyes_progress::Progress;
+use keys::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Names of the assets that the code looks up in [`NamedAssets`].
+pub mod keys {
+    pub const WALKING_AUDIO: &str = "walking";
+    pub const GLOWY_INTERIOR_TEXTURE: &str = "glowy_interior";
+    pub const SKY_TEXTURE: &str = "sky";
+}
+
+/// Assets the code needs regardless of the level, loaded at startup together with the ones used by characters.
+/// Everything else is loaded with the levels that declare it, see
+/// [`LevelAssetList`](crate::file_system_interaction::level_serialization::LevelAssetList).
+const GLOBAL_ASSETS: &[(AssetKind, &str)] = &[
+    (AssetKind::Audio, WALKING_AUDIO),
+    (AssetKind::Texture, GLOWY_INTERIOR_TEXTURE),
+    (AssetKind::Texture, SKY_TEXTURE),
+];
+
+/// Lists the assets of the game by category as `name: path`, e.g. `"sky": "textures/sky.jpg"`.
+/// Read from `assets/game.manifest.ron`.
+#[derive(Debug, Clone, PartialEq, TypeUuid, Serialize, Deserialize, Default)]
+#[uuid = "5e4a3b0c-8f63-4d8e-a5c1-2b7f0e9d1c46"]
+pub struct AssetManifest {
+    #[serde(default)]
+    pub scenes: HashMap<String, String>,
+    #[serde(default)]
+    pub animations: HashMap<String, String>,
+    #[serde(default)]
+    pub audio: HashMap<String, String>,
+    #[serde(default)]
+    pub textures: HashMap<String, String>,
+}
+
+#[derive(AssetCollection, Resource, Clone)]
+pub struct ManifestAssets {
+    #[asset(path = "game.manifest.ron")]
+    pub manifest: Handle<AssetManifest>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssetKind {
+    Scene,
+    Animation,
+    Audio,
+    Texture,
+}
+
+impl fmt::Display for AssetKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Scene => "scene",
+            Self::Animation => "animation",
+            Self::Audio => "audio",
+            Self::Texture => "texture",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The assets listed in the [`AssetManifest`] that are currently loaded, looked up by their name.
+#[derive(Debug, Clone, Resource)]
+pub struct NamedAssets {
+    manifest: AssetManifest,
+    /// Never unloaded, see [`GLOBAL_ASSETS`].
+    global: HashSet<(AssetKind, String)>,
+    pub scenes: HashMap<String, Handle<Scene>>,
+    pub animations: HashMap<String, Handle<AnimationClip>>,
+    pub audio: HashMap<String, Handle<AudioSource>>,
+    pub textures: HashMap<String, Handle<Image>>,
+}
+
+impl NamedAssets {
+    fn new(manifest: AssetManifest) -> Self {
+        Self {
+            manifest,
+            global: default(),
+            scenes: default(),
+            animations: default(),
+            audio: default(),
+            textures: default(),
+        }
+    }
+
+    /// Starts loading an asset unless it is already loaded. Returns `None` if the manifest does not list it.
+    pub fn request(
+        &mut self,
+        kind: AssetKind,
+        name: &str,
+        asset_server: &AssetServer,
+    ) -> Option<HandleId> {
+        fn request_from<T: Asset>(
+            handles: &mut HashMap<String, Handle<T>>,
+            paths: &HashMap<String, String>,
+            name: &str,
+            asset_server: &AssetServer,
+        ) -> Option<HandleId> {
+            let path = paths.get(name)?;
+            let handle = handles
+                .entry(name.to_owned())
+                .or_insert_with(|| asset_server.load(path.as_str()));
+            Some(handle.id())
+        }
+        let manifest = &self.manifest;
+        match kind {
+            AssetKind::Scene => {
+                request_from(&mut self.scenes, &manifest.scenes, name, asset_server)
+            }
+            AssetKind::Animation => request_from(
+                &mut self.animations,
+                &manifest.animations,
+                name,
+                asset_server,
+            ),
+            AssetKind::Audio => request_from(&mut self.audio, &manifest.audio, name, asset_server),
+            AssetKind::Texture => {
+                request_from(&mut self.textures, &manifest.textures, name, asset_server)
+            }
+        }
+    }
+
+    /// Drops the handles of all assets that are neither global nor in `keep`,
+    /// so that the asset server unloads them once nothing else uses them.
+    pub fn retain(&mut self, keep: &HashSet<(AssetKind, String)>) {
+        let global = &self.global;
+        let is_kept = |kind: AssetKind, name: &String| {
+            let key = (kind, name.clone());
+            global.contains(&key) || keep.contains(&key)
+        };
+        self.scenes
+            .retain(|name, _| is_kept(AssetKind::Scene, name));
+        self.animations
+            .retain(|name, _| is_kept(AssetKind::Animation, name));
+        self.audio.retain(|name, _| is_kept(AssetKind::Audio, name));
+        self.textures
+            .retain(|name, _| is_kept(AssetKind::Texture, name));
+    }
+
+    /// Missing scenes are logged and replaced by an empty handle.
+    pub fn scene(&self, name: &str) -> Handle<Scene> {
+        get_or_report(&self.scenes, AssetKind::Scene, name)
+    }
+
+    pub fn animation(&self, name: &str) -> Handle<AnimationClip> {
+        get_or_report(&self.animations, AssetKind::Animation, name)
+    }
+
+    pub fn audio(&self, name: &str) -> Handle<AudioSource> {
+        get_or_report(&self.audio, AssetKind::Audio, name)
+    }
+
+    pub fn texture(&self, name: &str) -> Handle<Image> {
+        get_or_report(&self.textures, AssetKind::Texture, name)
+    }
+
+    /// Whether the manifest lists the asset, regardless of whether it is loaded.
+    pub fn contains(&self, kind: AssetKind, name: &str) -> bool {
+        match kind {
+            AssetKind::Scene => self.manifest.scenes.contains_key(name),
+            AssetKind::Animation => self.manifest.animations.contains_key(name),
+            AssetKind::Audio => self.manifest.audio.contains_key(name),
+            AssetKind::Texture => self.manifest.textures.contains_key(name),
+        }
+    }
+
+    fn handle_ids(&self) -> impl Iterator<Item = (AssetKind, &str, HandleId)> {
+        fn ids<T: Asset>(
+            kind: AssetKind,
+            handles: &HashMap<String, Handle<T>>,
+        ) -> impl Iterator<Item = (AssetKind, &str, HandleId)> {
+            handles
+                .iter()
+                .map(move |(name, handle)| (kind, name.as_str(), handle.id()))
+        }
+        ids(AssetKind::Scene, &self.scenes)
+            .chain(ids(AssetKind::Animation, &self.animations))
+            .chain(ids(AssetKind::Audio, &self.audio))
+            .chain(ids(AssetKind::Texture, &self.textures))
+    }
+}
+
+fn get_or_report<T: Asset>(
+    handles: &HashMap<String, Handle<T>>,
+    kind: AssetKind,
+    name: &str,
+) -> Handle<T> {
+    handles.get(name).cloned().unwrap_or_else(|| {
+        error!("No {kind} named \"{name}\" is loaded. Is it missing from the asset manifest or the level's asset list?");
+        default()
+    })
+}
+
+/// Starts loading the global assets as soon as the manifest and the characters are loaded,
+/// and reports how many of them are done.
+pub(super) fn load_named_assets(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    manifest_assets: Option<Res<ManifestAssets>>,
+    manifests: Res<Assets<AssetManifest>>,
+    character_assets: Option<Res<CharacterAssets>>,
+    characters: Res<Assets<CharacterDefinition>>,
+    named_assets: Option<Res<NamedAssets>>,
+) -> Progress {
+    if let Some(named_assets) = named_assets {
+        let mut progress = Progress { done: 0, total: 0 };
+        for (.., id) in named_assets.handle_ids() {
+            progress.total += 1;
+            // Failed assets are reported once loading is over instead of blocking the game
+            if matches!(
+                asset_server.get_load_state(id),
+                LoadState::Loaded | LoadState::Failed
+            ) {
+                progress.done += 1;
+            }
+        }
+        return progress;
+    }
+    let (Some(manifest_assets), Some(character_assets)) = (manifest_assets, character_assets) else {
+        return Progress { done: 0, total: 1 };
+    };
+    let Some(manifest) = manifests.get(&manifest_assets.manifest) else {
+        return Progress { done: 0, total: 1 };
+    };
+
+    let mut named_assets = NamedAssets::new(manifest.clone());
+    let character_assets = character_assets
+        .characters
+        .values()
+        .filter_map(|handle| characters.get(handle))
+        .flat_map(|character| character.required_assets());
+    let global: HashSet<_> = GLOBAL_ASSETS
+        .iter()
+        .copied()
+        .chain(character_assets)
+        .map(|(kind, name)| (kind, name.to_owned()))
+        .collect();
+    let mut missing: Vec<_> = global
+        .iter()
+        .filter(|(kind, name)| named_assets.request(*kind, name, &asset_server).is_none())
+        .map(|(kind, name)| format!("\n- {kind} \"{name}\""))
+        .collect();
+    if !missing.is_empty() {
+        missing.sort();
+        error!(
+            "The asset manifest is missing {} assets used by the code or by characters:{}",
+            missing.len(),
+            missing.concat()
+        );
+    }
+    named_assets.global = global;
+    commands.insert_resource(named_assets);
+    Progress { done: 0, total: 1 }
+}
+
+pub(super) fn report_failed_assets(asset_server: Res<AssetServer>, named_assets: Res<NamedAssets>) {
+    for (kind, name, id) in named_assets.handle_ids() {
+        if asset_server.get_load_state(id) == LoadState::Failed {
+            error!("Failed to load {kind} \"{name}\" listed in the asset manifest");
+        }
+    }
+}
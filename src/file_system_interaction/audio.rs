@@ -1,4 +1,5 @@
-use crate::file_system_interaction::asset_loading::AudioAssets;
+use crate::file_system_interaction::asset_loading::{keys, NamedAssets};
+use crate::file_system_interaction::config::GameConfig;
 use crate::GameState;
 use bevy::prelude::*;
 use bevy_kira_audio::prelude::{Audio, *};
@@ -6,7 +7,11 @@ use bevy_kira_audio::prelude::{Audio, *};
 /// Handles initialization of all sounds.
 pub fn internal_audio_plugin(app: &mut App) {
     app.add_plugin(AudioPlugin)
-        .add_system(init_audio.in_schedule(OnExit(GameState::Loading)));
+        .add_system(init_audio.in_schedule(OnExit(GameState::Loading)))
+        .add_system(
+            apply_volume
+                .run_if(resource_exists::<GameConfig>().and_then(resource_changed::<GameConfig>())),
+        );
 }
 
 #[derive(Debug, Clone, Resource)]
@@ -14,12 +19,16 @@ pub struct AudioHandles {
     pub walking: Handle<AudioInstance>,
 }
 
-fn init_audio(mut commands: Commands, audio_assets: Res<AudioAssets>, audio: Res<Audio>) {
+fn init_audio(mut commands: Commands, assets: Res<NamedAssets>, audio: Res<Audio>) {
     audio.pause();
     let handle = audio
-        .play(audio_assets.walking.clone())
+        .play(assets.audio(keys::WALKING_AUDIO))
         .looped()
         .with_volume(0.8)
         .handle();
     commands.insert_resource(AudioHandles { walking: handle });
 }
+
+fn apply_volume(config: Res<GameConfig>, audio: Res<Audio>) {
+    audio.set_volume(config.audio.volume as f64);
+}
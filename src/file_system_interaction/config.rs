@@ -2,6 +2,12 @@ use bevy::prelude::*;
 use bevy::reflect::TypeUuid;
 use serde::{Deserialize, Serialize};
 
+mod validation;
+pub use validation::{format_config_problems, ConfigProblem};
+
+/// Tweakable values of the game, read from `config/config.game.toml`.
+/// Keys missing from the file fall back to the values shipped with the game, see [`GameConfig::validate`]
+/// for what happens to values that make no sense.
 #[derive(
     Debug,
     Clone,
@@ -16,15 +22,19 @@ use serde::{Deserialize, Serialize};
 )]
 #[reflect(Serialize, Deserialize, Resource)]
 #[uuid = "93a7c64b-4d6e-4420-b8c1-dfca481d9387"]
+#[serde(default)]
 pub struct GameConfig {
     pub camera: Camera,
     pub characters: Characters,
     pub player: Player,
     pub dialog: Dialog,
+    pub autosave: Autosave,
+    pub audio: Audio,
 }
 
-#[derive(Debug, Clone, PartialEq, Reflect, FromReflect, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Reflect, FromReflect, Serialize, Deserialize)]
 #[reflect(Serialize, Deserialize)]
+#[serde(default)]
 pub struct Camera {
     pub fixed_angle: FixedAngle,
     pub first_person: FirstPerson,
@@ -33,8 +43,21 @@ pub struct Camera {
     pub mouse_sensitivity_y: f32,
 }
 
-#[derive(Debug, Clone, PartialEq, Reflect, FromReflect, Serialize, Deserialize, Default)]
+impl Default for Camera {
+    fn default() -> Self {
+        Self {
+            fixed_angle: default(),
+            first_person: default(),
+            third_person: default(),
+            mouse_sensitivity_x: 8e-4,
+            mouse_sensitivity_y: 5e-4,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Reflect, FromReflect, Serialize, Deserialize)]
 #[reflect(Serialize, Deserialize)]
+#[serde(default)]
 pub struct FixedAngle {
     pub min_distance: f32,
     pub max_distance: f32,
@@ -43,25 +66,59 @@ pub struct FixedAngle {
     pub translation_smoothing: f32,
     pub zoom_in_smoothing: f32,
     pub zoom_out_smoothing: f32,
+    /// In degrees.
     pub pitch: f32,
 }
 
-#[derive(Debug, Clone, PartialEq, Reflect, FromReflect, Serialize, Deserialize, Default)]
+impl Default for FixedAngle {
+    fn default() -> Self {
+        Self {
+            min_distance: 10.0,
+            max_distance: 20.0,
+            zoom_speed: 0.7,
+            rotation_smoothing: 1.0,
+            translation_smoothing: 0.9,
+            zoom_in_smoothing: 0.2,
+            zoom_out_smoothing: 1.2,
+            pitch: -80.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Reflect, FromReflect, Serialize, Deserialize)]
 #[reflect(Serialize, Deserialize)]
+#[serde(default)]
 pub struct FirstPerson {
     pub translation_smoothing: f32,
     pub rotation_smoothing: f32,
+    /// In degrees.
     pub max_pitch: f32,
+    /// In degrees.
     pub min_pitch: f32,
     pub tracking_smoothing: f32,
 }
 
-#[derive(Debug, Clone, PartialEq, Reflect, FromReflect, Serialize, Deserialize, Default)]
+impl Default for FirstPerson {
+    fn default() -> Self {
+        Self {
+            translation_smoothing: 0.05,
+            rotation_smoothing: 0.1,
+            max_pitch: 80.0,
+            min_pitch: -80.0,
+            tracking_smoothing: 1.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Reflect, FromReflect, Serialize, Deserialize)]
 #[reflect(Serialize, Deserialize)]
+#[serde(default)]
 pub struct ThirdPerson {
     pub translation_smoothing: f32,
     pub rotation_smoothing: f32,
+    /// In degrees.
     pub max_pitch: f32,
+    /// In degrees.
     pub min_pitch: f32,
     pub min_distance: f32,
     pub max_distance: f32,
@@ -72,25 +129,110 @@ pub struct ThirdPerson {
     pub zoom_out_smoothing: f32,
 }
 
-#[derive(Debug, Clone, PartialEq, Reflect, FromReflect, Serialize, Deserialize, Default)]
+impl Default for ThirdPerson {
+    fn default() -> Self {
+        Self {
+            translation_smoothing: 0.9,
+            rotation_smoothing: 0.5,
+            max_pitch: 80.0,
+            min_pitch: -80.0,
+            min_distance: 1.0,
+            max_distance: 10.0,
+            zoom_speed: 0.7,
+            min_distance_to_objects: 0.4,
+            tracking_smoothing: 0.8,
+            zoom_in_smoothing: 0.2,
+            zoom_out_smoothing: 1.2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Reflect, FromReflect, Serialize, Deserialize)]
 #[reflect(Serialize, Deserialize)]
+#[serde(default)]
 pub struct Characters {
     pub model_sync_smoothing: f32,
     pub rotation_smoothing: f32,
 }
 
-#[derive(Debug, Clone, PartialEq, Reflect, FromReflect, Serialize, Deserialize, Default)]
+impl Default for Characters {
+    fn default() -> Self {
+        Self {
+            model_sync_smoothing: 0.15,
+            rotation_smoothing: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Reflect, FromReflect, Serialize, Deserialize)]
 #[reflect(Serialize, Deserialize)]
+#[serde(default)]
 pub struct Player {
     pub rotate_to_speaker_smoothness: f32,
     pub sprint_effect_speed_threshold: f32,
     pub fov_saturation_speed: f32,
+    /// In radians.
     pub min_fov: f32,
+    /// In radians.
     pub max_fov: f32,
 }
 
-#[derive(Debug, Clone, PartialEq, Reflect, FromReflect, Serialize, Deserialize, Default)]
+impl Default for Player {
+    fn default() -> Self {
+        Self {
+            rotate_to_speaker_smoothness: 3.0,
+            sprint_effect_speed_threshold: 7.0,
+            fov_saturation_speed: 12.0,
+            min_fov: 0.75,
+            max_fov: 1.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Reflect, FromReflect, Serialize, Deserialize)]
 #[reflect(Serialize, Deserialize)]
+#[serde(default)]
 pub struct Dialog {
     pub base_letters_per_second: f32,
 }
+
+impl Default for Dialog {
+    fn default() -> Self {
+        Self {
+            base_letters_per_second: 60.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Reflect, FromReflect, Serialize, Deserialize)]
+#[reflect(Serialize, Deserialize)]
+#[serde(default)]
+pub struct Autosave {
+    /// Seconds of playtime between timed autosaves. `0` disables timed autosaves.
+    pub interval: f32,
+    /// Number of autosave slots that are cycled through, overwriting the oldest one.
+    pub slots: usize,
+}
+
+impl Default for Autosave {
+    fn default() -> Self {
+        Self {
+            interval: 300.0,
+            slots: 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Reflect, FromReflect, Serialize, Deserialize)]
+#[reflect(Serialize, Deserialize)]
+#[serde(default)]
+pub struct Audio {
+    /// Master volume from `0` (muted) to `1`.
+    pub volume: f32,
+}
+
+impl Default for Audio {
+    fn default() -> Self {
+        Self { volume: 1.0 }
+    }
+}
@@ -0,0 +1,368 @@
+use crate::file_system_interaction::config::GameConfig;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+/// A value of the [`GameConfig`] that was replaced by [`GameConfig::validate`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigProblem {
+    /// NaN or infinite. Replaced by the default value.
+    NotFinite {
+        field: &'static str,
+        replacement: f32,
+    },
+    /// Clamped to the nearest allowed value.
+    OutOfRange {
+        field: &'static str,
+        value: f32,
+        range: RangeInclusive<f32>,
+        replacement: f32,
+    },
+    /// A minimum that is larger than its maximum. Both are replaced by their default values.
+    Inverted {
+        min_field: &'static str,
+        max_field: &'static str,
+        min: f32,
+        max: f32,
+    },
+}
+
+impl fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFinite { field, replacement } => {
+                write!(f, "{field} is not a finite number, using {replacement} instead")
+            }
+            Self::OutOfRange {
+                field,
+                value,
+                range,
+                replacement,
+            } => write!(
+                f,
+                "{field} is {value}, but must be between {} and {}, using {replacement} instead",
+                range.start(),
+                range.end()
+            ),
+            Self::Inverted {
+                min_field,
+                max_field,
+                min,
+                max,
+            } => write!(
+                f,
+                "{min_field} ({min}) is larger than {max_field} ({max}), using the defaults for both instead"
+            ),
+        }
+    }
+}
+
+pub fn format_config_problems(problems: &[ConfigProblem]) -> String {
+    problems
+        .iter()
+        .map(|problem| format!("\n- {problem}"))
+        .collect()
+}
+
+/// Any finite number.
+const FINITE: RangeInclusive<f32> = f32::MIN..=f32::MAX;
+const POSITIVE: RangeInclusive<f32> = f32::MIN_POSITIVE..=f32::MAX;
+const NON_NEGATIVE: RangeInclusive<f32> = 0.0..=f32::MAX;
+/// Degrees between straight down and straight up.
+const PITCH: RangeInclusive<f32> = -90.0..=90.0;
+/// Radians. Bevy's perspective projection breaks down at 180°.
+const FOV: RangeInclusive<f32> = 0.01..=3.0;
+const VOLUME: RangeInclusive<f32> = 0.0..=1.0;
+
+/// A number in the [`GameConfig`] and its name in the config file.
+struct Field {
+    name: &'static str,
+    get: fn(&mut GameConfig) -> &mut f32,
+}
+
+/// `field!(player.min_fov)` is the [`Field`] for `config.player.min_fov`.
+macro_rules! field {
+    ($($path:ident).+) => {
+        Field {
+            name: stringify!($($path).+),
+            get: |config| &mut config.$($path).+,
+        }
+    };
+}
+
+/// The allowed values of every number in the [`GameConfig`]. Values outside are clamped, NaN and infinity are
+/// replaced by the default.
+const RANGES: &[(Field, RangeInclusive<f32>)] = &[
+    (field!(camera.mouse_sensitivity_x), FINITE),
+    (field!(camera.mouse_sensitivity_y), FINITE),
+    (field!(camera.fixed_angle.min_distance), POSITIVE),
+    (field!(camera.fixed_angle.max_distance), POSITIVE),
+    (field!(camera.fixed_angle.zoom_speed), NON_NEGATIVE),
+    (field!(camera.fixed_angle.rotation_smoothing), NON_NEGATIVE),
+    (
+        field!(camera.fixed_angle.translation_smoothing),
+        NON_NEGATIVE,
+    ),
+    (field!(camera.fixed_angle.zoom_in_smoothing), NON_NEGATIVE),
+    (field!(camera.fixed_angle.zoom_out_smoothing), NON_NEGATIVE),
+    (field!(camera.fixed_angle.pitch), PITCH),
+    (
+        field!(camera.first_person.translation_smoothing),
+        NON_NEGATIVE,
+    ),
+    (field!(camera.first_person.rotation_smoothing), NON_NEGATIVE),
+    (field!(camera.first_person.tracking_smoothing), NON_NEGATIVE),
+    (field!(camera.first_person.min_pitch), PITCH),
+    (field!(camera.first_person.max_pitch), PITCH),
+    (
+        field!(camera.third_person.translation_smoothing),
+        NON_NEGATIVE,
+    ),
+    (field!(camera.third_person.rotation_smoothing), NON_NEGATIVE),
+    (field!(camera.third_person.tracking_smoothing), NON_NEGATIVE),
+    (field!(camera.third_person.zoom_speed), NON_NEGATIVE),
+    (
+        field!(camera.third_person.min_distance_to_objects),
+        NON_NEGATIVE,
+    ),
+    (field!(camera.third_person.zoom_in_smoothing), NON_NEGATIVE),
+    (field!(camera.third_person.zoom_out_smoothing), NON_NEGATIVE),
+    (field!(camera.third_person.min_pitch), PITCH),
+    (field!(camera.third_person.max_pitch), PITCH),
+    (field!(camera.third_person.min_distance), POSITIVE),
+    (field!(camera.third_person.max_distance), POSITIVE),
+    (field!(characters.model_sync_smoothing), NON_NEGATIVE),
+    (field!(characters.rotation_smoothing), NON_NEGATIVE),
+    (field!(player.rotate_to_speaker_smoothness), NON_NEGATIVE),
+    (field!(player.sprint_effect_speed_threshold), NON_NEGATIVE),
+    // The speed effect divides by this
+    (field!(player.fov_saturation_speed), POSITIVE),
+    (field!(player.min_fov), FOV),
+    (field!(player.max_fov), FOV),
+    (field!(dialog.base_letters_per_second), POSITIVE),
+    (field!(autosave.interval), NON_NEGATIVE),
+    (field!(audio.volume), VOLUME),
+];
+
+/// Pairs of a minimum and a maximum. Checked after [`RANGES`].
+const ORDERED: &[(Field, Field)] = &[
+    (
+        field!(camera.fixed_angle.min_distance),
+        field!(camera.fixed_angle.max_distance),
+    ),
+    (
+        field!(camera.first_person.min_pitch),
+        field!(camera.first_person.max_pitch),
+    ),
+    (
+        field!(camera.third_person.min_pitch),
+        field!(camera.third_person.max_pitch),
+    ),
+    (
+        field!(camera.third_person.min_distance),
+        field!(camera.third_person.max_distance),
+    ),
+    (field!(player.min_fov), field!(player.max_fov)),
+];
+
+impl GameConfig {
+    /// Replaces values that would break the game, e.g. a negative zoom speed or a `min_fov` larger than `max_fov`,
+    /// and returns what was replaced. A valid config is left as it is.
+    pub fn validate(&mut self) -> Vec<ConfigProblem> {
+        let mut defaults = GameConfig::default();
+        let mut problems = Vec::new();
+
+        for (field, range) in RANGES {
+            let default = *(field.get)(&mut defaults);
+            let value = (field.get)(self);
+            if !value.is_finite() {
+                problems.push(ConfigProblem::NotFinite {
+                    field: field.name,
+                    replacement: default,
+                });
+                *value = default;
+            } else if !range.contains(value) {
+                let replacement = value.clamp(*range.start(), *range.end());
+                problems.push(ConfigProblem::OutOfRange {
+                    field: field.name,
+                    value: *value,
+                    range: range.clone(),
+                    replacement,
+                });
+                *value = replacement;
+            }
+        }
+
+        for (min_field, max_field) in ORDERED {
+            let (min, max) = (*(min_field.get)(self), *(max_field.get)(self));
+            if min <= max {
+                continue;
+            }
+            problems.push(ConfigProblem::Inverted {
+                min_field: min_field.name,
+                max_field: max_field.name,
+                min,
+                max,
+            });
+            *(min_field.get)(self) = *(min_field.get)(&mut defaults);
+            *(max_field.get)(self) = *(max_field.get)(&mut defaults);
+        }
+
+        if self.autosave.slots == 0 {
+            problems.push(ConfigProblem::OutOfRange {
+                field: "autosave.slots",
+                value: 0.0,
+                range: 1.0..=f32::MAX,
+                replacement: 1.0,
+            });
+            self.autosave.slots = 1;
+        }
+
+        problems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_config_is_valid() {
+        let mut config = GameConfig::default();
+        assert_eq!(config.validate(), vec![]);
+        assert_eq!(config, GameConfig::default());
+    }
+
+    #[test]
+    fn shipped_config_matches_defaults() {
+        let config: GameConfig =
+            toml::from_str(include_str!("../../../assets/config/config.game.toml")).unwrap();
+        assert_eq!(config, GameConfig::default());
+    }
+
+    #[test]
+    fn missing_values_fall_back_to_defaults() {
+        let config: GameConfig = toml::from_str(
+            r#"
+            [player]
+            max_fov = 2.0
+            "#,
+        )
+        .unwrap();
+        let mut expected = GameConfig::default();
+        expected.player.max_fov = 2.0;
+        assert_eq!(config, expected);
+    }
+
+    #[test]
+    fn valid_values_are_kept() {
+        let mut config = GameConfig::default();
+        config.player.min_fov = 0.5;
+        config.autosave.interval = 0.0;
+        config.camera.mouse_sensitivity_x = -1e-3;
+        let expected = config.clone();
+        assert_eq!(config.validate(), vec![]);
+        assert_eq!(config, expected);
+    }
+
+    #[test]
+    fn nan_and_infinity_are_reset_to_defaults() {
+        let mut config = GameConfig::default();
+        config.camera.mouse_sensitivity_y = f32::NAN;
+        config.dialog.base_letters_per_second = f32::INFINITY;
+        let defaults = GameConfig::default();
+
+        let problems = config.validate();
+
+        assert_eq!(
+            problems,
+            vec![
+                ConfigProblem::NotFinite {
+                    field: "camera.mouse_sensitivity_y",
+                    replacement: defaults.camera.mouse_sensitivity_y,
+                },
+                ConfigProblem::NotFinite {
+                    field: "dialog.base_letters_per_second",
+                    replacement: defaults.dialog.base_letters_per_second,
+                },
+            ]
+        );
+        assert_eq!(config, defaults);
+    }
+
+    #[test]
+    fn out_of_range_values_are_clamped() {
+        let mut config = GameConfig::default();
+        config.autosave.interval = -5.0;
+        config.camera.fixed_angle.pitch = -120.0;
+        config.audio.volume = 2.0;
+
+        let problems = config.validate();
+
+        assert_eq!(problems.len(), 3);
+        assert!(problems.contains(&ConfigProblem::OutOfRange {
+            field: "autosave.interval",
+            value: -5.0,
+            range: NON_NEGATIVE,
+            replacement: 0.0,
+        }));
+        assert_eq!(config.autosave.interval, 0.0);
+        assert_eq!(config.camera.fixed_angle.pitch, -90.0);
+        assert_eq!(config.audio.volume, 1.0);
+    }
+
+    #[test]
+    fn zero_is_not_positive() {
+        let mut config = GameConfig::default();
+        config.player.fov_saturation_speed = 0.0;
+
+        assert_eq!(config.validate().len(), 1);
+        assert!(config.player.fov_saturation_speed > 0.0);
+    }
+
+    #[test]
+    fn inverted_min_and_max_are_reset_to_defaults() {
+        let mut config = GameConfig::default();
+        config.player.min_fov = 1.4;
+        config.player.max_fov = 0.8;
+        let defaults = GameConfig::default();
+
+        let problems = config.validate();
+
+        assert_eq!(
+            problems,
+            vec![ConfigProblem::Inverted {
+                min_field: "player.min_fov",
+                max_field: "player.max_fov",
+                min: 1.4,
+                max: 0.8,
+            }]
+        );
+        assert_eq!(config.player.min_fov, defaults.player.min_fov);
+        assert_eq!(config.player.max_fov, defaults.player.max_fov);
+    }
+
+    #[test]
+    fn ranges_are_checked_before_order() {
+        let mut config = GameConfig::default();
+        // Clamped to 3.0 first, which is still larger than the maximum
+        config.player.min_fov = 10.0;
+
+        let problems = config.validate();
+
+        assert_eq!(problems.len(), 2);
+        assert!(matches!(
+            problems[1],
+            ConfigProblem::Inverted { min, .. } if min == 3.0
+        ));
+        assert_eq!(config.player, GameConfig::default().player);
+    }
+
+    #[test]
+    fn zero_autosave_slots_become_one() {
+        let mut config = GameConfig::default();
+        config.autosave.slots = 0;
+
+        assert_eq!(config.validate().len(), 1);
+        assert_eq!(config.autosave.slots, 1);
+    }
+}
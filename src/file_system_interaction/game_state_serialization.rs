@@ -1,27 +1,55 @@
-use crate::file_system_interaction::level_serialization::{CurrentLevel, WorldLoadRequest};
-use crate::level_instantiation::spawning::GameObject;
+use crate::file_system_interaction::level_serialization::{
+    CurrentLevel, LoadedLevel, WorldLoadFinished, WorldLoadRequest,
+};
+use crate::level_instantiation::spawning::{GameObject, SpawnData};
+use crate::player_control::camera::{CameraState, IngameCamera};
 use crate::player_control::player_embodiment::Player;
 use crate::world_interaction::condition::ActiveConditions;
 use crate::world_interaction::dialog::{CurrentDialog, DialogEvent};
+use crate::world_interaction::objective::CurrentObjective;
 use crate::GameState;
-use anyhow::{Context, Result};
+use anyhow::Result;
 use bevy::prelude::*;
+use bevy_dolly::prelude::Rig;
 use bevy_mod_sysfail::macros::*;
-use chrono::prelude::Local;
-use glob::glob;
+use chrono::prelude::{Local, Utc};
+use seldom_fn_plugin::FnPluginExt;
 use serde::{Deserialize, Serialize};
 use spew::prelude::*;
-use std::borrow::Cow;
-use std::fs;
-use std::path::{Path, PathBuf};
+
+mod autosave;
+mod format;
+mod migration;
+mod save_storage;
+mod slots;
+mod world_delta;
+pub use autosave::AUTOSAVE_SLOT_PREFIX;
+pub use format::SaveFormat;
+pub use migration::{read_save_version, SaveMigration, SaveMigrations, CURRENT_SAVE_VERSION};
+pub use save_storage::{is_save_sealed, seal_save, unseal_save, SaveStorage};
+pub use slots::{list_save_slots, GameDeleteRequest, Playtime, SaveMetadata, SaveSlot, SaveSlots};
+use world_delta::{compute_world_delta, DeltaObjects, PendingLoad};
+pub use world_delta::{AddedObject, ChangedObject, CharacterMotion, PendingMotions, WorldDelta};
 
 pub fn game_state_serialization_plugin(app: &mut App) {
-    app.add_event::<GameSaveRequest>()
+    app.register_type::<SaveFormat>()
+        .init_resource::<SaveFormat>()
+        .init_resource::<SaveMigrations>()
+        .init_resource::<SaveStorage>()
+        .fn_plugin(slots::save_slots_plugin)
+        .fn_plugin(world_delta::world_delta_plugin)
+        .fn_plugin(autosave::autosave_plugin)
+        .add_event::<GameSaveRequest>()
         .add_event::<GameLoadRequest>()
         .add_systems(
             (
                 handle_load_requests,
-                handle_save_requests.run_if(resource_exists::<CurrentLevel>()),
+                spawn_saved_player.run_if(resource_exists::<PendingLoad>()),
+                handle_save_requests.run_if(
+                    resource_exists::<CurrentLevel>().and_then(resource_exists::<LoadedLevel>()),
+                ),
+                // Saving reads the whole world, so the written slots are listed afterwards
+                slots::refresh_save_slots.run_if(on_event::<GameSaveRequest>()),
             )
                 .chain()
                 .in_set(OnUpdate(GameState::Playing)),
@@ -30,22 +58,36 @@ pub fn game_state_serialization_plugin(app: &mut App) {
 
 #[derive(Debug, Clone, Eq, PartialEq, Resource, Serialize, Deserialize, Default)]
 pub struct GameSaveRequest {
-    pub filename: Option<String>,
+    /// The [`SaveSlot`] to write to. Existing slots are overwritten. If `None`, a new slot is created.
+    pub slot: Option<String>,
+    /// If `None`, an overwritten slot keeps its label.
+    pub label: Option<String>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Resource, Serialize, Deserialize, Default)]
 pub struct GameLoadRequest {
-    pub filename: Option<String>,
+    /// The [`SaveSlot`] to load. If `None`, the newest slot is loaded.
+    pub slot: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Resource, Serialize, Deserialize, Default)]
-struct SaveModel {
-    scene: String,
+pub struct SaveModel {
+    /// See [`CURRENT_SAVE_VERSION`]. Saves without this field predate versioning and are treated as version 0.
+    #[serde(default)]
+    pub version: u32,
+    #[serde(default)]
+    pub metadata: SaveMetadata,
+    pub scene: String,
     #[serde(default, skip_serializing_if = "ActiveConditions::is_empty")]
-    conditions: ActiveConditions,
-    player_transform: Transform,
+    pub conditions: ActiveConditions,
+    pub player_transform: Transform,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dialog_event: Option<DialogEvent>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    dialog_event: Option<DialogEvent>,
+    pub camera: Option<CameraState>,
+    /// Changes to the level since it was loaded. Replayed after the level has been spawned.
+    #[serde(default, skip_serializing_if = "WorldDelta::is_empty")]
+    pub world: WorldDelta,
 }
 
 #[sysfail(log(level = "error"))]
@@ -53,70 +95,79 @@ fn handle_load_requests(
     mut commands: Commands,
     mut load_events: EventReader<GameLoadRequest>,
     mut loader: EventWriter<WorldLoadRequest>,
-    mut spawner: EventWriter<SpawnEvent<GameObject, Transform>>,
-    mut dialog_event_writer: EventWriter<DialogEvent>,
+    migrations: Res<SaveMigrations>,
+    save_storage: Res<SaveStorage>,
+    save_slots: Res<SaveSlots>,
 ) -> Result<()> {
     for load in load_events.iter() {
-        let path = match load
-            .filename
-            .as_ref()
-            .map(|filename| anyhow::Ok(Some(get_save_path(filename.clone()))))
-            .unwrap_or_else(|| {
-                let mut saves: Vec<_> = glob("./saves/*.sav.ron")
-                    .context("Failed to read glob pattern")?
-                    .filter_map(|entry| entry.ok())
-                    .filter(|entry| entry.is_file())
-                    .collect();
-                saves.sort_by_cached_key(|f| {
-                    f.metadata()
-                        .expect("Failed to read file metadata")
-                        .modified()
-                        .expect("Failed to read file modified time")
-                });
-                Ok(saves.last().map(|entry| entry.to_owned()))
-            })? {
-            Some(path) => path,
+        let slot = match load
+            .slot
+            .clone()
+            .or_else(|| save_slots.newest().map(|slot| slot.id.clone()))
+        {
+            Some(slot) => slot,
             None => {
-                error!("Failed to load save: No filename provided and no saves found on disk");
+                error!("Failed to load save: No slot provided and no saves found on disk");
                 continue;
             }
         };
-        let serialized = match fs::read_to_string(&path) {
-            Ok(serialized) => {
-                info!("Successfully read save at {}", path.to_string_lossy());
-                serialized
+        let save_model = match save_storage.read(&slot, &migrations) {
+            Ok(save_model) => {
+                info!("Successfully read save \"{slot}\"");
+                save_model
             }
             Err(e) => {
-                error!(
-                    "Failed to read save {:?} at {:?}: {}",
-                    &load.filename, path, e
-                );
-                continue;
-            }
-        };
-        let save_model: SaveModel = match ron::from_str(&serialized) {
-            Ok(save_model) => save_model,
-            Err(e) => {
-                error!(
-                    "Failed to deserialize save {:?} at {:?}: {}",
-                    &load.filename, path, e
-                );
+                error!("Failed to read save \"{slot}\": {e:#}");
                 continue;
             }
         };
         loader.send(WorldLoadRequest {
             filename: save_model.scene,
+            spawn_point: None,
         });
-        if let Some(dialog_event) = save_model.dialog_event {
+        commands.insert_resource(PendingLoad {
+            conditions: save_model.conditions,
+            camera: save_model.camera,
+            world: save_model.world,
+            player_transform: save_model.player_transform,
+            dialog_event: save_model.dialog_event,
+            level_loaded: false,
+        });
+        commands.insert_resource(Playtime(save_model.metadata.playtime));
+        commands.insert_resource(CurrentObjective(save_model.metadata.objective));
+    }
+    Ok(())
+}
+
+/// Spawns the player of a save once its level is there.
+fn spawn_saved_player(
+    mut commands: Commands,
+    mut finished_events: EventReader<WorldLoadFinished>,
+    mut pending_load: ResMut<PendingLoad>,
+    mut spawner: EventWriter<SpawnEvent<GameObject, SpawnData>>,
+    mut dialog_event_writer: EventWriter<DialogEvent>,
+) {
+    for finished in finished_events.iter() {
+        // Saves place the player themselves
+        if pending_load.level_loaded || finished.request.spawn_point.is_some() {
+            continue;
+        }
+        if !finished.success {
+            commands.remove_resource::<PendingLoad>();
+            return;
+        }
+        if let Some(dialog_event) = pending_load.dialog_event.clone() {
             dialog_event_writer.send(dialog_event);
         }
-        commands.insert_resource(save_model.conditions);
-
         spawner.send(
-            SpawnEvent::with_data(GameObject::Player, save_model.player_transform).delay_frames(2),
+            SpawnEvent::with_data(
+                GameObject::PLAYER,
+                SpawnData::from(pending_load.player_transform),
+            )
+            .delay_frames(2),
         );
+        pending_load.level_loaded = true;
     }
-    Ok(())
 }
 
 #[sysfail(log(level = "error"))]
@@ -125,46 +176,66 @@ fn handle_save_requests(
     conditions: Res<ActiveConditions>,
     dialog: Option<Res<CurrentDialog>>,
     player_query: Query<&GlobalTransform, With<Player>>,
+    camera_query: Query<(&IngameCamera, &Rig)>,
     current_level: Res<CurrentLevel>,
+    playtime: Res<Playtime>,
+    objective: Res<CurrentObjective>,
+    save_slots: Res<SaveSlots>,
+    save_storage: Res<SaveStorage>,
+    save_format: Res<SaveFormat>,
+    objects: DeltaObjects,
+    loaded_level: Res<LoadedLevel>,
 ) -> Result<()> {
     let dialog = dialog.map(|dialog| dialog.clone());
     for save in save_events.iter() {
+        let world = compute_world_delta(&loaded_level.expanded.objects, &objects);
+        let camera = camera_query
+            .iter()
+            .next()
+            .map(|(camera, rig)| CameraState::read(camera, rig));
         for player in &player_query {
             let dialog_event = dialog.clone().map(|dialog| DialogEvent {
                 dialog: dialog.id,
                 source: dialog.source,
                 page: Some(dialog.current_page),
             });
+            let slot = save
+                .slot
+                .clone()
+                .unwrap_or_else(|| Local::now().format("%Y-%m-%d_%H-%M-%S").to_string());
+            let label = save.label.clone().unwrap_or_else(|| {
+                save_slots
+                    .get(&slot)
+                    .map(|existing| existing.metadata.label.clone())
+                    .unwrap_or_default()
+            });
             let save_model = SaveModel {
+                version: CURRENT_SAVE_VERSION,
+                metadata: SaveMetadata {
+                    label,
+                    playtime: playtime.0,
+                    timestamp: Some(Utc::now()),
+                    objective: objective.0.clone(),
+                },
                 scene: current_level.scene.clone(),
                 conditions: conditions.clone(),
                 dialog_event,
                 player_transform: player.compute_transform(),
+                camera: camera.clone(),
+                world: world.clone(),
             };
-            let serialized = match ron::to_string(&save_model) {
-                Ok(string) => string,
+            let serialized = match save_format.serialize(&save_model) {
+                Ok(serialized) => serialized,
                 Err(e) => {
-                    error!("Failed to save world: {}", e);
+                    error!("Failed to save world: {:#}", e);
                     continue;
                 }
             };
-            let filename = save
-                .filename
-                .clone()
-                .unwrap_or_else(|| Local::now().to_rfc2822().replace(':', "-"));
-            let path = get_save_path(filename.clone());
-            let dir = path.parent().context("Failed to get save directory")?;
-            fs::create_dir_all(dir).context("Failed to create save directory")?;
-            fs::write(&path, serialized)
-                .unwrap_or_else(|e| error!("Failed to write save {filename}: {e}"));
-
-            info!("Successfully saved game at {}", path.to_string_lossy());
+            match save_storage.write(&slot, &serialized) {
+                Ok(()) => info!("Successfully saved game to slot \"{slot}\""),
+                Err(e) => error!("Failed to write save \"{slot}\": {e:#}"),
+            }
         }
     }
     Ok(())
 }
-
-fn get_save_path(filename: impl Into<Cow<'static, str>>) -> PathBuf {
-    let filename = filename.into().to_string();
-    Path::new("saves").join(filename).with_extension("sav.ron")
-}
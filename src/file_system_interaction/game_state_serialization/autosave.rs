@@ -0,0 +1,162 @@
+use crate::file_system_interaction::config::GameConfig;
+use crate::file_system_interaction::game_state_serialization::world_delta::PendingLoad;
+use crate::file_system_interaction::game_state_serialization::{GameSaveRequest, SaveSlots};
+use crate::file_system_interaction::level_serialization::CurrentLevel;
+use crate::level_instantiation::spawning::objects::checkpoint::Checkpoint;
+use crate::player_control::player_embodiment::Player;
+use crate::world_interaction::condition::{ActiveConditions, ConditionAddEvent, ConditionId};
+use crate::world_interaction::dialog::CurrentDialog;
+use crate::GameState;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use std::time::Duration;
+
+/// Autosaves are written to the slots `autosave-1` to `autosave-n`, where `n` is set in [`GameConfig`].
+pub const AUTOSAVE_SLOT_PREFIX: &str = "autosave-";
+const AUTOSAVE_LABEL: &str = "Autosave";
+/// Time given to a freshly loaded level to spawn all of its objects before it is autosaved.
+const LEVEL_SETTLE_SECONDS: f32 = 1.0;
+
+pub(super) fn autosave_plugin(app: &mut App) {
+    app.init_resource::<AutosaveScheduler>().add_systems(
+        (
+            schedule_autosave_on_level_transition.run_if(
+                resource_exists::<CurrentLevel>().and_then(resource_changed::<CurrentLevel>()),
+            ),
+            schedule_autosave_on_conditions,
+            schedule_autosave_on_checkpoints,
+            tick_autosave_timers,
+            write_autosave.run_if(
+                resource_exists::<CurrentLevel>()
+                    .and_then(any_with_component::<Player>())
+                    .and_then(not(resource_exists::<PendingLoad>()))
+                    .and_then(not(resource_exists::<CurrentDialog>())),
+            ),
+        )
+            .chain()
+            .in_set(OnUpdate(GameState::Playing)),
+    );
+}
+
+/// Collects the reasons to autosave and holds on to them until saving is possible,
+/// e.g. after a dialog has ended.
+#[derive(Debug, Clone, Resource, Default)]
+struct AutosaveScheduler {
+    due: bool,
+    /// Conditions that triggered the autosave but have not been added to [`ActiveConditions`] yet.
+    awaited_conditions: Vec<ConditionId>,
+    /// Runs after a level transition. The autosave waits for it to finish.
+    settling: Option<Timer>,
+    /// Runs between timed autosaves. Restarted by every autosave.
+    interval: Timer,
+}
+
+fn schedule_autosave_on_level_transition(
+    mut scheduler: ResMut<AutosaveScheduler>,
+    pending_load: Option<Res<PendingLoad>>,
+) {
+    scheduler.awaited_conditions.clear();
+    if pending_load.is_some() {
+        // The level was entered by loading a save, so there is nothing new to save.
+        scheduler.due = false;
+        scheduler.settling = None;
+        scheduler.interval.reset();
+        return;
+    }
+    scheduler.due = true;
+    scheduler.settling = Some(Timer::from_seconds(LEVEL_SETTLE_SECONDS, TimerMode::Once));
+}
+
+fn schedule_autosave_on_conditions(
+    mut scheduler: ResMut<AutosaveScheduler>,
+    mut condition_events: EventReader<ConditionAddEvent>,
+) {
+    for event in condition_events.iter() {
+        scheduler.due = true;
+        scheduler.awaited_conditions.push(event.0.clone());
+    }
+}
+
+fn schedule_autosave_on_checkpoints(
+    mut scheduler: ResMut<AutosaveScheduler>,
+    mut collision_events: EventReader<CollisionEvent>,
+    player_query: Query<(), With<Player>>,
+    checkpoint_query: Query<(), With<Checkpoint>>,
+) {
+    for event in collision_events.iter() {
+        let CollisionEvent::Started(entity_a, entity_b, _kind) = event else {
+            continue;
+        };
+        let entered_checkpoint =
+            [(*entity_a, *entity_b), (*entity_b, *entity_a)]
+                .iter()
+                .any(|(player, checkpoint)| {
+                    player_query.contains(*player) && checkpoint_query.contains(*checkpoint)
+                });
+        if entered_checkpoint {
+            scheduler.due = true;
+        }
+    }
+}
+
+fn tick_autosave_timers(
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    mut scheduler: ResMut<AutosaveScheduler>,
+) {
+    if let Some(settling) = scheduler.settling.as_mut() {
+        settling.tick(time.delta());
+    }
+
+    let interval = Duration::from_secs_f32(config.autosave.interval.max(0.));
+    if interval.is_zero() {
+        return;
+    }
+    if scheduler.interval.duration() != interval {
+        scheduler.interval = Timer::new(interval, TimerMode::Repeating);
+    }
+    if scheduler.interval.tick(time.delta()).just_finished() {
+        scheduler.due = true;
+    }
+}
+
+fn write_autosave(
+    mut scheduler: ResMut<AutosaveScheduler>,
+    mut save_requests: EventWriter<GameSaveRequest>,
+    conditions: Res<ActiveConditions>,
+    save_slots: Res<SaveSlots>,
+    config: Res<GameConfig>,
+) {
+    if !scheduler.due {
+        return;
+    }
+    if let Some(settling) = &scheduler.settling && !settling.finished() {
+        return;
+    }
+    if !scheduler
+        .awaited_conditions
+        .iter()
+        .all(|condition| conditions.0.contains(condition))
+    {
+        return;
+    }
+
+    let slot = next_autosave_slot(&save_slots, config.autosave.slots);
+    info!("Autosaving to slot \"{slot}\"");
+    save_requests.send(GameSaveRequest {
+        slot: Some(slot),
+        label: Some(AUTOSAVE_LABEL.to_owned()),
+    });
+    scheduler.due = false;
+    scheduler.awaited_conditions.clear();
+    scheduler.settling = None;
+    scheduler.interval.reset();
+}
+
+/// Picks an unused autosave slot if there is one, otherwise the one that was written longest ago.
+fn next_autosave_slot(save_slots: &SaveSlots, slot_count: usize) -> String {
+    (1..=slot_count.max(1))
+        .map(|n| format!("{AUTOSAVE_SLOT_PREFIX}{n}"))
+        .min_by_key(|id| save_slots.get(id).map(|slot| slot.timestamp))
+        .expect("There is always at least one autosave slot")
+}
@@ -0,0 +1,83 @@
+use anyhow::{Context, Result};
+use bevy::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Marks a save written in [`SaveFormat::Binary`]. RON saves never start with a null byte.
+const BINARY_MAGIC: &[u8] = b"\0TMIE-SAVE\n";
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// How a [`SaveModel`](super::SaveModel) is encoded. Loading detects the format on its own,
+/// so this resource only decides how new saves are written.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Resource,
+    Reflect,
+    FromReflect,
+    Serialize,
+    Deserialize,
+    Default,
+)]
+#[reflect(Resource, Serialize, Deserialize)]
+pub enum SaveFormat {
+    /// Human readable, handy for debugging.
+    #[default]
+    Ron,
+    /// MessagePack compressed with zstd. Much smaller and faster to load.
+    Binary,
+}
+
+impl SaveFormat {
+    pub fn detect(data: &[u8]) -> Self {
+        if data.starts_with(BINARY_MAGIC) {
+            Self::Binary
+        } else {
+            Self::Ron
+        }
+    }
+
+    /// Includes the leading dot.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Ron => ".sav.ron",
+            Self::Binary => ".sav",
+        }
+    }
+
+    pub fn serialize<T: Serialize>(self, value: &T) -> Result<Vec<u8>> {
+        match self {
+            Self::Ron => ron::to_string(value)
+                .map(String::into_bytes)
+                .context("Failed to serialize RON"),
+            Self::Binary => {
+                // Fields are written with their names so that `#[serde(default)]` and `skip_serializing_if`
+                // work just like in RON.
+                let encoded =
+                    rmp_serde::to_vec_named(value).context("Failed to encode MessagePack")?;
+                let compressed = zstd::encode_all(encoded.as_slice(), COMPRESSION_LEVEL)
+                    .context("Failed to compress save")?;
+                Ok([BINARY_MAGIC, &compressed].concat())
+            }
+        }
+    }
+
+    pub fn deserialize<T: DeserializeOwned>(self, data: &[u8]) -> Result<T> {
+        match self {
+            Self::Ron => {
+                let serialized = std::str::from_utf8(data).context("Save is not valid UTF-8")?;
+                ron::from_str(serialized).context("Failed to parse RON")
+            }
+            Self::Binary => {
+                let compressed = data
+                    .strip_prefix(BINARY_MAGIC)
+                    .context("Binary save is missing its header")?;
+                let encoded = zstd::decode_all(compressed).context("Failed to decompress save")?;
+                rmp_serde::from_slice(&encoded).context("Failed to decode MessagePack")
+            }
+        }
+    }
+}
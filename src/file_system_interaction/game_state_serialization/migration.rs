@@ -0,0 +1,841 @@
+use crate::file_system_interaction::game_state_serialization::{SaveFormat, SaveModel};
+use anyhow::{bail, ensure, Context, Result};
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::fmt;
+
+/// The version of [`SaveModel`] written by this build of the game.
+/// Bump this and register a [`SaveMigration`] from the previous version whenever the save format changes.
+/// The migration tests read the saves in `fixtures`, so add one written by the previous version as well.
+pub const CURRENT_SAVE_VERSION: u32 = 5;
+
+/// A single step upgrading a serialized save from `from_version` to `from_version + 1`.
+/// The migrated save must be written in the same [`SaveFormat`] it was read in.
+#[derive(Clone, Copy)]
+pub struct SaveMigration {
+    pub from_version: u32,
+    /// Human readable summary of what changed, used in error messages.
+    pub description: &'static str,
+    pub migrate: fn(SaveFormat, &[u8]) -> Result<Vec<u8>>,
+}
+
+impl fmt::Debug for SaveMigration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SaveMigration")
+            .field("from_version", &self.from_version)
+            .field("description", &self.description)
+            .finish_non_exhaustive()
+    }
+}
+
+/// All known [`SaveMigration`]s. Saves written by an older version of the game are run through
+/// these in order until they reach [`CURRENT_SAVE_VERSION`].
+#[derive(Debug, Clone, Resource)]
+pub struct SaveMigrations(Vec<SaveMigration>);
+
+impl Default for SaveMigrations {
+    fn default() -> Self {
+        Self(vec![
+            SaveMigration {
+                from_version: 0,
+                description: "add version field",
+                migrate: v0::migrate,
+            },
+            SaveMigration {
+                from_version: 1,
+                description: "add slot metadata",
+                migrate: v1::migrate,
+            },
+            SaveMigration {
+                from_version: 2,
+                description: "add world state",
+                migrate: v2::migrate,
+            },
+            SaveMigration {
+                from_version: 3,
+                description: "reference objects by persistent ID",
+                migrate: v3::migrate,
+            },
+            SaveMigration {
+                from_version: 4,
+                description: "identify object types by registry ID",
+                migrate: v4::migrate,
+            },
+        ])
+    }
+}
+
+impl SaveMigrations {
+    /// Registers a migration, replacing any existing one for the same version.
+    pub fn register(&mut self, migration: SaveMigration) -> &mut Self {
+        self.0
+            .retain(|existing| existing.from_version != migration.from_version);
+        self.0.push(migration);
+        self
+    }
+
+    pub fn get(&self, from_version: u32) -> Option<&SaveMigration> {
+        self.0
+            .iter()
+            .find(|migration| migration.from_version == from_version)
+    }
+
+    /// Upgrades a serialized save to [`CURRENT_SAVE_VERSION`] without deserializing it into a [`SaveModel`].
+    /// The format is kept as is.
+    pub fn migrate_to_current<'a>(&self, serialized: &'a [u8]) -> Result<Cow<'a, [u8]>> {
+        let format = SaveFormat::detect(serialized);
+        let mut version = read_save_version(serialized)?;
+        if version > CURRENT_SAVE_VERSION {
+            bail!(
+                "Save has version {version}, but this build of the game only supports versions up to {CURRENT_SAVE_VERSION}"
+            );
+        }
+        let mut serialized = Cow::Borrowed(serialized);
+        while version < CURRENT_SAVE_VERSION {
+            let migration = self
+                .get(version)
+                .with_context(|| format!("No migration registered for save version {version}"))?;
+            let migrated = (migration.migrate)(format, &serialized).with_context(|| {
+                format!(
+                    "Failed to migrate save from version {version} to {} ({})",
+                    version + 1,
+                    migration.description
+                )
+            })?;
+            let migrated_version = read_save_version(&migrated)?;
+            ensure!(
+                migrated_version == version + 1,
+                "Migration from save version {version} produced version {migrated_version} instead of {}",
+                version + 1
+            );
+            serialized = Cow::Owned(migrated);
+            version = migrated_version;
+        }
+        Ok(serialized)
+    }
+
+    /// Upgrades a serialized save to [`CURRENT_SAVE_VERSION`] and deserializes it.
+    pub fn read(&self, serialized: &[u8]) -> Result<SaveModel> {
+        let serialized = self.migrate_to_current(serialized)?;
+        SaveFormat::detect(&serialized)
+            .deserialize(&serialized)
+            .context("Failed to deserialize save after migrating it")
+    }
+}
+
+/// Reads only the version of a serialized save. Saves written before versioning was introduced have version 0.
+pub fn read_save_version(serialized: &[u8]) -> Result<u32> {
+    #[derive(Deserialize)]
+    struct SaveHeader {
+        #[serde(default)]
+        version: u32,
+    }
+    SaveFormat::detect(serialized)
+        .deserialize::<SaveHeader>(serialized)
+        .map(|header| header.version)
+        .context("Failed to read save version")
+}
+
+// Each version module only uses its own types and the ones of the other version modules, never the live ones.
+// That way, later changes to the game's types don't change what a migration reads or writes.
+// The one exception are object properties, see `v5::ChangedObjectV5::properties`.
+
+/// Saves written before the version field existed.
+mod v0 {
+    use super::v1::{DialogEventV1, SaveModelV1};
+    use crate::file_system_interaction::game_state_serialization::SaveFormat;
+    use anyhow::{Context, Result};
+    use bevy::prelude::*;
+    use bevy::utils::HashSet;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct SaveModelV0 {
+        scene: String,
+        #[serde(default)]
+        conditions: ConditionsV0,
+        player_transform: Transform,
+        #[serde(default)]
+        dialog_event: Option<DialogEventV1>,
+    }
+
+    /// The game's `ActiveConditions`, which has always been written as a newtype.
+    #[derive(Serialize, Deserialize, Default)]
+    pub(super) struct ConditionsV0(HashSet<String>);
+
+    pub(super) fn migrate(format: SaveFormat, serialized: &[u8]) -> Result<Vec<u8>> {
+        let old: SaveModelV0 = format
+            .deserialize(serialized)
+            .context("Failed to parse version 0 save")?;
+        let new = SaveModelV1 {
+            version: 1,
+            scene: old.scene,
+            conditions: old.conditions,
+            player_transform: old.player_transform,
+            dialog_event: old.dialog_event,
+        };
+        format
+            .serialize(&new)
+            .context("Failed to serialize version 1 save")
+    }
+}
+
+/// Saves written before save slots had metadata.
+mod v1 {
+    use super::v0::ConditionsV0;
+    use super::v2::{SaveMetadataV2, SaveModelV2};
+    use crate::file_system_interaction::game_state_serialization::SaveFormat;
+    use anyhow::{Context, Result};
+    use bevy::prelude::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct SaveModelV1 {
+        pub(super) version: u32,
+        pub(super) scene: String,
+        #[serde(default)]
+        pub(super) conditions: ConditionsV0,
+        pub(super) player_transform: Transform,
+        #[serde(default)]
+        pub(super) dialog_event: Option<DialogEventV1>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct DialogEventV1 {
+        pub(super) dialog: String,
+        pub(super) source: Entity,
+        pub(super) page: Option<String>,
+    }
+
+    pub(super) fn migrate(format: SaveFormat, serialized: &[u8]) -> Result<Vec<u8>> {
+        let old: SaveModelV1 = format
+            .deserialize(serialized)
+            .context("Failed to parse version 1 save")?;
+        let new = SaveModelV2 {
+            version: 2,
+            metadata: SaveMetadataV2::default(),
+            scene: old.scene,
+            conditions: old.conditions,
+            player_transform: old.player_transform,
+            dialog_event: old.dialog_event,
+        };
+        format
+            .serialize(&new)
+            .context("Failed to serialize version 2 save")
+    }
+}
+
+/// Saves written before the world state was persisted.
+mod v2 {
+    use super::v0::ConditionsV0;
+    use super::v1::DialogEventV1;
+    use super::v3::{SaveModelV3, WorldDeltaV3};
+    use crate::file_system_interaction::game_state_serialization::SaveFormat;
+    use anyhow::{Context, Result};
+    use bevy::prelude::*;
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Serialize};
+    use std::time::Duration;
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct SaveModelV2 {
+        pub(super) version: u32,
+        #[serde(default)]
+        pub(super) metadata: SaveMetadataV2,
+        pub(super) scene: String,
+        #[serde(default)]
+        pub(super) conditions: ConditionsV0,
+        pub(super) player_transform: Transform,
+        #[serde(default)]
+        pub(super) dialog_event: Option<DialogEventV1>,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub(super) struct SaveMetadataV2 {
+        #[serde(default)]
+        pub(super) label: String,
+        #[serde(default)]
+        pub(super) playtime: Duration,
+        #[serde(default)]
+        pub(super) timestamp: Option<DateTime<Utc>>,
+        #[serde(default)]
+        pub(super) objective: Option<String>,
+    }
+
+    pub(super) fn migrate(format: SaveFormat, serialized: &[u8]) -> Result<Vec<u8>> {
+        let old: SaveModelV2 = format
+            .deserialize(serialized)
+            .context("Failed to parse version 2 save")?;
+        let new = SaveModelV3 {
+            version: 3,
+            metadata: old.metadata,
+            scene: old.scene,
+            conditions: old.conditions,
+            player_transform: old.player_transform,
+            dialog_event: old.dialog_event,
+            world: WorldDeltaV3::default(),
+        };
+        format
+            .serialize(&new)
+            .context("Failed to serialize version 3 save")
+    }
+}
+
+/// Saves that referenced level objects by their index in the level file and dialog sources by [`Entity`].
+mod v3 {
+    use super::v0::ConditionsV0;
+    use super::v1::DialogEventV1;
+    use super::v2::SaveMetadataV2;
+    use super::v4::{AddedObjectV4, ChangedObjectV4, GameObjectV4, SaveModelV4, WorldDeltaV4};
+    use crate::file_system_interaction::game_state_serialization::SaveFormat;
+    use anyhow::{Context, Result};
+    use bevy::prelude::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct SaveModelV3 {
+        pub(super) version: u32,
+        #[serde(default)]
+        pub(super) metadata: SaveMetadataV2,
+        pub(super) scene: String,
+        #[serde(default)]
+        pub(super) conditions: ConditionsV0,
+        pub(super) player_transform: Transform,
+        #[serde(default)]
+        pub(super) dialog_event: Option<DialogEventV1>,
+        #[serde(default)]
+        pub(super) world: WorldDeltaV3,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub(super) struct WorldDeltaV3 {
+        #[serde(default)]
+        removed: Vec<usize>,
+        #[serde(default)]
+        changed: Vec<ChangedObjectV3>,
+        #[serde(default)]
+        added: Vec<AddedObjectV3>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct ChangedObjectV3 {
+        index: usize,
+        transform: Transform,
+        #[serde(default)]
+        motion: Option<CharacterMotionV3>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct AddedObjectV3 {
+        object: GameObjectV4,
+        transform: Transform,
+        #[serde(default)]
+        motion: Option<CharacterMotionV3>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct CharacterMotionV3 {
+        pub(super) velocity: VelocityV3,
+        pub(super) walking: WalkingV3,
+    }
+
+    /// Rapier's `Velocity`.
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct VelocityV3 {
+        pub(super) linvel: Vec3,
+        pub(super) angvel: Vec3,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct WalkingV3 {
+        pub(super) ground_acceleration: f32,
+        pub(super) sprinting_acceleration: f32,
+        pub(super) aerial_acceleration: f32,
+        pub(super) braking_acceleration: f32,
+        pub(super) stopping_speed: f32,
+        pub(super) direction: Option<Vec3>,
+        pub(super) sprinting: bool,
+    }
+
+    /// Level files that predate persistent IDs were converted by using each object's index as its ID.
+    fn id_from_index(index: usize) -> u64 {
+        index as u64
+    }
+
+    /// Objects added to the world get an ID derived from their index in [`WorldDeltaV3::added`],
+    /// so that migrating the same save twice gives the same result.
+    fn id_of_added(index: usize) -> u64 {
+        // Keeps the IDs far away from the level indices used by `id_from_index`.
+        const SALT: u64 = 0x6164_6465_645f_7633;
+        // SplitMix64 finalizer, like `PersistentId::nested`.
+        let mut id = SALT ^ (index as u64).wrapping_add(0x9e37_79b9_7f4a_7c15);
+        id = (id ^ (id >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        id = (id ^ (id >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        id ^ (id >> 31)
+    }
+
+    pub(super) fn migrate(format: SaveFormat, serialized: &[u8]) -> Result<Vec<u8>> {
+        let old: SaveModelV3 = format
+            .deserialize(serialized)
+            .context("Failed to parse version 3 save")?;
+        let new = SaveModelV4 {
+            version: 4,
+            metadata: old.metadata,
+            scene: old.scene,
+            conditions: old.conditions,
+            player_transform: old.player_transform,
+            // The entity a dialog was started by is meaningless outside of the session that saved it,
+            // so the dialog cannot be resumed.
+            dialog_event: None,
+            camera: None,
+            world: WorldDeltaV4 {
+                removed: old.world.removed.into_iter().map(id_from_index).collect(),
+                changed: old
+                    .world
+                    .changed
+                    .into_iter()
+                    .map(|changed| ChangedObjectV4 {
+                        id: id_from_index(changed.index),
+                        transform: changed.transform,
+                        motion: changed.motion,
+                    })
+                    .collect(),
+                added: old
+                    .world
+                    .added
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, added)| AddedObjectV4 {
+                        id: id_of_added(index),
+                        object: added.object,
+                        transform: added.transform,
+                        motion: added.motion,
+                    })
+                    .collect(),
+            },
+        };
+        format
+            .serialize(&new)
+            .context("Failed to serialize version 4 save")
+    }
+}
+
+/// Saves that identified object types by a fixed enum instead of their ID in the
+/// [`GameObjectRegistry`](crate::level_instantiation::spawning::GameObjectRegistry).
+mod v4 {
+    use super::v0::ConditionsV0;
+    use super::v2::SaveMetadataV2;
+    use super::v3::CharacterMotionV3;
+    use super::v5::{AddedObjectV5, ChangedObjectV5, SaveModelV5, WorldDeltaV5};
+    use crate::file_system_interaction::game_state_serialization::SaveFormat;
+    use anyhow::{Context, Result};
+    use bevy::prelude::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct SaveModelV4 {
+        pub(super) version: u32,
+        #[serde(default)]
+        pub(super) metadata: SaveMetadataV2,
+        pub(super) scene: String,
+        #[serde(default)]
+        pub(super) conditions: ConditionsV0,
+        pub(super) player_transform: Transform,
+        #[serde(default)]
+        pub(super) dialog_event: Option<DialogEventV4>,
+        #[serde(default)]
+        pub(super) camera: Option<CameraStateV4>,
+        #[serde(default)]
+        pub(super) world: WorldDeltaV4,
+    }
+
+    /// Dialog sources are referenced by their persistent ID.
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct DialogEventV4 {
+        pub(super) dialog: String,
+        pub(super) source: u64,
+        pub(super) page: Option<String>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct CameraStateV4 {
+        pub(super) kind: CameraKindV4,
+        pub(super) desired_distance: f32,
+        pub(super) yaw_degrees: f32,
+        pub(super) pitch_degrees: f32,
+        #[serde(default)]
+        pub(super) arm_offset: Option<Vec3>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) enum CameraKindV4 {
+        ThirdPerson,
+        FirstPerson,
+        FixedAngle,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub(super) struct WorldDeltaV4 {
+        #[serde(default)]
+        pub(super) removed: Vec<u64>,
+        #[serde(default)]
+        pub(super) changed: Vec<ChangedObjectV4>,
+        #[serde(default)]
+        pub(super) added: Vec<AddedObjectV4>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct ChangedObjectV4 {
+        pub(super) id: u64,
+        pub(super) transform: Transform,
+        #[serde(default)]
+        pub(super) motion: Option<CharacterMotionV3>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct AddedObjectV4 {
+        pub(super) id: u64,
+        pub(super) object: GameObjectV4,
+        pub(super) transform: Transform,
+        #[serde(default)]
+        pub(super) motion: Option<CharacterMotionV3>,
+    }
+
+    /// The object types that existed before they were registered by ID.
+    #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+    pub(super) enum GameObjectV4 {
+        Empty,
+        Box,
+        Triangle,
+        Sphere,
+        Capsule,
+        Sunlight,
+        PointLight,
+        Npc,
+        Player,
+        Level,
+        Orb,
+        Camera,
+        Skydome,
+        Checkpoint,
+    }
+
+    impl GameObjectV4 {
+        /// The ID the object type was registered under.
+        fn id(self) -> &'static str {
+            match self {
+                Self::Empty => "Empty",
+                Self::Box => "Box",
+                Self::Triangle => "Triangle",
+                Self::Sphere => "Sphere",
+                Self::Capsule => "Capsule",
+                Self::Sunlight => "Sunlight",
+                Self::PointLight => "PointLight",
+                Self::Npc => "Npc",
+                Self::Player => "Player",
+                Self::Level => "Level",
+                Self::Orb => "Orb",
+                Self::Camera => "Camera",
+                Self::Skydome => "Skydome",
+                Self::Checkpoint => "Checkpoint",
+            }
+        }
+    }
+
+    pub(super) fn migrate(format: SaveFormat, serialized: &[u8]) -> Result<Vec<u8>> {
+        let old: SaveModelV4 = format
+            .deserialize(serialized)
+            .context("Failed to parse version 4 save")?;
+        let new = SaveModelV5 {
+            version: 5,
+            metadata: old.metadata,
+            scene: old.scene,
+            conditions: old.conditions,
+            player_transform: old.player_transform,
+            dialog_event: old.dialog_event,
+            camera: old.camera,
+            world: WorldDeltaV5 {
+                removed: old.world.removed,
+                changed: old
+                    .world
+                    .changed
+                    .into_iter()
+                    .map(|changed| ChangedObjectV5 {
+                        id: changed.id,
+                        transform: changed.transform,
+                        motion: changed.motion,
+                        properties: default(),
+                    })
+                    .collect(),
+                added: old
+                    .world
+                    .added
+                    .into_iter()
+                    .map(|added| AddedObjectV5 {
+                        id: added.id,
+                        object: added.object.id().to_owned(),
+                        transform: added.transform,
+                        motion: added.motion,
+                        properties: default(),
+                    })
+                    .collect(),
+            },
+        };
+        format
+            .serialize(&new)
+            .context("Failed to serialize version 5 save")
+    }
+}
+
+/// The current version, as written by [`SaveModel`]. The migration to the next version goes here once there is one.
+mod v5 {
+    use super::v0::ConditionsV0;
+    use super::v2::SaveMetadataV2;
+    use super::v3::CharacterMotionV3;
+    use super::v4::{CameraStateV4, DialogEventV4};
+    use crate::level_instantiation::spawning::ObjectProperties;
+    use bevy::prelude::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct SaveModelV5 {
+        pub(super) version: u32,
+        #[serde(default)]
+        pub(super) metadata: SaveMetadataV2,
+        pub(super) scene: String,
+        #[serde(default)]
+        pub(super) conditions: ConditionsV0,
+        pub(super) player_transform: Transform,
+        #[serde(default)]
+        pub(super) dialog_event: Option<DialogEventV4>,
+        #[serde(default)]
+        pub(super) camera: Option<CameraStateV4>,
+        #[serde(default)]
+        pub(super) world: WorldDeltaV5,
+    }
+
+    #[derive(Serialize, Deserialize, Default)]
+    pub(super) struct WorldDeltaV5 {
+        #[serde(default)]
+        pub(super) removed: Vec<u64>,
+        #[serde(default)]
+        pub(super) changed: Vec<ChangedObjectV5>,
+        #[serde(default)]
+        pub(super) added: Vec<AddedObjectV5>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct ChangedObjectV5 {
+        pub(super) id: u64,
+        pub(super) transform: Transform,
+        #[serde(default)]
+        pub(super) motion: Option<CharacterMotionV3>,
+        /// The properties of an object are declared by its registration, which is responsible for keeping them
+        /// readable. This is the only live type used by a version module.
+        #[serde(default)]
+        pub(super) properties: ObjectProperties,
+    }
+
+    /// Object types are identified by their ID in the registry.
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct AddedObjectV5 {
+        pub(super) id: u64,
+        pub(super) object: String,
+        pub(super) transform: Transform,
+        #[serde(default)]
+        pub(super) motion: Option<CharacterMotionV3>,
+        /// See [`ChangedObjectV5::properties`].
+        #[serde(default)]
+        pub(super) properties: ObjectProperties,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level_instantiation::spawning::{
+        builtin_object_registry, GameObject, GlowColor, PersistentId,
+    };
+    use crate::player_control::camera::IngameCameraKind;
+    use crate::world_interaction::condition::ConditionId;
+    use crate::world_interaction::dialog::{DialogEvent, DialogId};
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+
+    /// Saves written by earlier versions of the game. Never edit these, add a new one for new versions instead.
+    const FIXTURES: [(u32, &str); 6] = [
+        (0, include_str!("fixtures/v0.sav.ron")),
+        (1, include_str!("fixtures/v1.sav.ron")),
+        (2, include_str!("fixtures/v2.sav.ron")),
+        (3, include_str!("fixtures/v3.sav.ron")),
+        (4, include_str!("fixtures/v4.sav.ron")),
+        (5, include_str!("fixtures/v5.sav.ron")),
+    ];
+
+    fn read_fixture(version: u32) -> SaveModel {
+        let (_, fixture) = FIXTURES[version as usize];
+        // Registers the types of object properties
+        builtin_object_registry();
+        SaveMigrations::default()
+            .read(fixture.as_bytes())
+            .unwrap_or_else(|error| panic!("Failed to read version {version} fixture: {error:?}"))
+    }
+
+    /// Converts a RON fixture to [`SaveFormat::Binary`] by going through the types of its version.
+    fn to_binary(version: u32, fixture: &str) -> Vec<u8> {
+        fn convert<T: Serialize + DeserializeOwned>(fixture: &str) -> Vec<u8> {
+            let save: T = SaveFormat::Ron.deserialize(fixture.as_bytes()).unwrap();
+            SaveFormat::Binary.serialize(&save).unwrap()
+        }
+        match version {
+            0 => convert::<v0::SaveModelV0>(fixture),
+            1 => convert::<v1::SaveModelV1>(fixture),
+            2 => convert::<v2::SaveModelV2>(fixture),
+            3 => convert::<v3::SaveModelV3>(fixture),
+            4 => convert::<v4::SaveModelV4>(fixture),
+            5 => convert::<v5::SaveModelV5>(fixture),
+            _ => unreachable!("No fixture for version {version}"),
+        }
+    }
+
+    #[test]
+    fn fixtures_have_their_version() {
+        for (version, fixture) in FIXTURES {
+            assert_eq!(read_save_version(fixture.as_bytes()).unwrap(), version);
+        }
+    }
+
+    #[test]
+    fn fixtures_migrate_to_current_version() {
+        for (version, _) in FIXTURES {
+            let save = read_fixture(version);
+            assert_eq!(save.version, CURRENT_SAVE_VERSION);
+            assert_eq!(save.scene, "old_town");
+            assert_eq!(
+                save.conditions.0,
+                [ConditionId("talked_to_follower".to_owned())]
+                    .into_iter()
+                    .collect()
+            );
+            assert_eq!(save.player_transform.translation, Vec3::new(1.0, 2.0, 3.0));
+        }
+    }
+
+    #[test]
+    fn migrated_saves_round_trip() {
+        for format in [SaveFormat::Ron, SaveFormat::Binary] {
+            for (version, _) in FIXTURES {
+                let save = read_fixture(version);
+                let serialized = format.serialize(&save).unwrap();
+                assert_eq!(
+                    SaveMigrations::default().read(&serialized).unwrap(),
+                    save,
+                    "Version {version} fixture changed after round trip through {format:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn binary_fixtures_migrate_like_ron_fixtures() {
+        for (version, fixture) in FIXTURES {
+            let binary = to_binary(version, fixture);
+            assert_eq!(SaveFormat::detect(&binary), SaveFormat::Binary);
+            assert_eq!(read_save_version(&binary).unwrap(), version);
+            assert_eq!(
+                SaveMigrations::default().read(&binary).unwrap(),
+                read_fixture(version),
+                "Version {version} fixture"
+            );
+        }
+    }
+
+    #[test]
+    fn metadata_is_defaulted_before_version_2() {
+        for version in [0, 1] {
+            assert_eq!(read_fixture(version).metadata, default());
+        }
+        let metadata = read_fixture(2).metadata;
+        assert_eq!(metadata.label, "Before the shrine");
+        assert_eq!(metadata.playtime, std::time::Duration::from_secs(754));
+        assert_eq!(metadata.objective.as_deref(), Some("Find the shrine"));
+        assert!(metadata.timestamp.is_some());
+    }
+
+    #[test]
+    fn dialogs_started_by_entities_are_dropped() {
+        for version in 0..4 {
+            assert_eq!(read_fixture(version).dialog_event, None);
+        }
+        assert_eq!(
+            read_fixture(4).dialog_event,
+            Some(DialogEvent {
+                dialog: DialogId::new("follower"),
+                source: PersistentId(7),
+                page: Some("greeting".to_owned().into()),
+            })
+        );
+    }
+
+    #[test]
+    fn version_3_world_references_objects_by_index() {
+        let world = read_fixture(3).world;
+        assert_eq!(world.removed, [PersistentId(2)]);
+        assert_eq!(world.changed.len(), 1);
+        assert_eq!(world.changed[0].id, PersistentId(5));
+        let motion = world.changed[0].motion.as_ref().unwrap();
+        assert_eq!(motion.velocity.linvel, Vec3::new(0.5, 0.0, 0.0));
+        assert_eq!(motion.walking.direction, Some(Vec3::X));
+        assert_eq!(world.added.len(), 1);
+        assert_eq!(world.added[0].object, GameObject::ORB);
+    }
+
+    #[test]
+    fn version_4_objects_are_identified_by_registry_id() {
+        let save = read_fixture(4);
+        let camera = save.camera.unwrap();
+        assert_eq!(camera.kind, IngameCameraKind::ThirdPerson);
+        assert_eq!(camera.arm_offset, None);
+        assert_eq!(save.world.removed, [PersistentId(2)]);
+        assert_eq!(save.world.changed[0].id, PersistentId(5));
+        assert_eq!(save.world.changed[0].motion, None);
+        assert_eq!(save.world.added[0].id, PersistentId(1234));
+        assert_eq!(save.world.added[0].object, GameObject::ORB);
+    }
+
+    #[test]
+    fn migrating_twice_gives_the_same_result() {
+        for (version, fixture) in FIXTURES {
+            let migrate = || {
+                SaveMigrations::default()
+                    .migrate_to_current(fixture.as_bytes())
+                    .unwrap()
+                    .into_owned()
+            };
+            assert_eq!(migrate(), migrate(), "Version {version} fixture");
+        }
+    }
+
+    #[test]
+    fn version_5_objects_keep_their_properties() {
+        let save = read_fixture(5);
+        assert_eq!(
+            save.world.changed[0].properties.get::<DialogId>(),
+            Some(&DialogId::new("follower"))
+        );
+        assert_eq!(
+            save.world.added[0].properties.get::<GlowColor>(),
+            Some(&GlowColor(Color::BLUE))
+        );
+
+        // Future migrations read version 5 saves through these types
+        let (_, fixture) = FIXTURES[5];
+        for format in [SaveFormat::Ron, SaveFormat::Binary] {
+            let frozen: v5::SaveModelV5 = SaveFormat::Ron.deserialize(fixture.as_bytes()).unwrap();
+            let serialized = format.serialize(&frozen).unwrap();
+            let reread: SaveModel = format.deserialize(&serialized).unwrap();
+            assert_eq!(reread, save, "Round trip through {format:?}");
+        }
+    }
+}
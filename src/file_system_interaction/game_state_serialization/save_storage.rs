@@ -0,0 +1,386 @@
+use crate::file_system_interaction::game_state_serialization::{
+    SaveFormat, SaveMigrations, SaveModel,
+};
+use crate::file_system_interaction::storage::{Storage, StorageBackend, StorageLocation};
+use anyhow::{bail, ensure, Context, Result};
+use bevy::prelude::*;
+use std::iter;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// Every save is stored under one of these extensions, depending on its [`SaveFormat`].
+const SAVE_FORMATS: [SaveFormat; 2] = [SaveFormat::Ron, SaveFormat::Binary];
+const BACKUP_DIR: &str = "backups";
+const CHECKSUM_PREFIX: &str = "// checksum: ";
+
+/// Where save slots are stored. Every write keeps the previous versions of the slot as backups,
+/// which are used when the save itself turns out to be corrupted.
+/// The underlying [`Storage`] is chosen by [`StorageBackend`].
+#[derive(Clone, Resource)]
+pub struct SaveStorage {
+    storage: Arc<dyn Storage>,
+    /// How many previous versions of each slot are kept.
+    pub backup_count: usize,
+}
+
+impl FromWorld for SaveStorage {
+    fn from_world(world: &mut World) -> Self {
+        let backend = *world.get_resource_or_insert_with(StorageBackend::default);
+        Self {
+            storage: backend.open_or_memory(StorageLocation::Saves),
+            backup_count: 3,
+        }
+    }
+}
+
+impl SaveStorage {
+    pub fn new(storage: impl Storage, backup_count: usize) -> Self {
+        Self {
+            storage: Arc::new(storage),
+            backup_count,
+        }
+    }
+
+    pub fn set_storage(&mut self, storage: Arc<dyn Storage>) {
+        self.storage = storage;
+    }
+
+    /// IDs of all slots in the storage, in no particular order.
+    pub fn slot_ids(&self) -> Result<Vec<String>> {
+        let mut ids: Vec<_> = self
+            .storage
+            .list()?
+            .into_iter()
+            .filter(|name| !name.contains('/'))
+            .filter_map(|name| split_extension(&name).map(|(id, _)| id.to_owned()))
+            .collect();
+        ids.sort();
+        ids.dedup();
+        Ok(ids)
+    }
+
+    pub fn modified(&self, slot: &str) -> Option<SystemTime> {
+        self.storage.modified(&self.find(slot)?)
+    }
+
+    /// Writes a serialized [`SaveModel`] to a slot, moving the previous contents of the slot into the backups.
+    /// The blob's extension is chosen by the [`SaveFormat`] of `serialized`.
+    pub fn write(&self, slot: &str, serialized: &[u8]) -> Result<()> {
+        self.rotate_backups(slot)?;
+        let format = SaveFormat::detect(serialized);
+        self.write_blob(slot, format, &seal_save(serialized))
+    }
+
+    /// Reads a slot and migrates it to the current version.
+    /// If the slot is corrupted, the newest backup that can be read is returned instead.
+    pub fn read(&self, slot: &str, migrations: &SaveMigrations) -> Result<SaveModel> {
+        let candidates = iter::once(slot.to_owned())
+            .chain((1..=self.backup_count).map(|n| backup_base(slot, n)));
+        let mut errors = Vec::new();
+        for name in candidates.filter_map(|base| self.find(&base)) {
+            match self.read_blob(&name, migrations) {
+                Ok(save_model) => {
+                    if !errors.is_empty() {
+                        warn!(
+                            "Save \"{slot}\" is corrupted, loaded backup {name} instead. Errors: {}",
+                            errors.join("; ")
+                        );
+                    }
+                    return Ok(save_model);
+                }
+                Err(e) => errors.push(format!("{name}: {e:#}")),
+            }
+        }
+        if errors.is_empty() {
+            bail!("No such save: {slot}");
+        }
+        bail!(
+            "Neither save \"{slot}\" nor any of its backups could be read: {}",
+            errors.join("; ")
+        )
+    }
+
+    /// Deletes a slot along with its backups.
+    pub fn delete(&self, slot: &str) -> Result<()> {
+        let name = self
+            .find(slot)
+            .with_context(|| format!("No such save: {slot}"))?;
+        self.storage.delete(&name)?;
+        for n in 1..=self.backup_count {
+            self.delete_all_formats(&backup_base(slot, n))?;
+        }
+        Ok(())
+    }
+
+    /// Finds the name of the blob stored for `base`, which is a slot ID or backup name without extension.
+    fn find(&self, base: &str) -> Option<String> {
+        SAVE_FORMATS
+            .iter()
+            .map(|format| format!("{base}{}", format.extension()))
+            .find(|name| self.storage.exists(name))
+    }
+
+    /// Writes `data` for `base` and removes any blob stored for `base` in another format.
+    fn write_blob(&self, base: &str, format: SaveFormat, data: &[u8]) -> Result<()> {
+        self.storage
+            .write(&format!("{base}{}", format.extension()), data)?;
+        for other in SAVE_FORMATS.iter().filter(|other| **other != format) {
+            let name = format!("{base}{}", other.extension());
+            if self.storage.exists(&name) {
+                self.storage.delete(&name)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn delete_all_formats(&self, base: &str) -> Result<()> {
+        while let Some(name) = self.find(base) {
+            self.storage.delete(&name)?;
+        }
+        Ok(())
+    }
+
+    fn read_blob(&self, name: &str, migrations: &SaveMigrations) -> Result<SaveModel> {
+        let data = self.storage.read(name)?;
+        let body = unseal_save(&data)?;
+        migrations.read(body)
+    }
+
+    /// Shifts every backup of a slot one place back, dropping the oldest, and copies the slot into the first place.
+    /// A corrupted slot is not backed up so that it can't push a valid backup out.
+    fn rotate_backups(&self, slot: &str) -> Result<()> {
+        if self.backup_count == 0 {
+            return Ok(());
+        }
+        let Some(current) = self.find(slot) else {
+            return Ok(());
+        };
+        let data = self.storage.read(&current)?;
+        if let Err(e) = unseal_save(&data) {
+            warn!("Not backing up corrupted save \"{slot}\": {e:#}");
+            return Ok(());
+        }
+        for n in (1..self.backup_count).rev() {
+            if let Some(older) = self.find(&backup_base(slot, n)) {
+                let older_data = self.storage.read(&older)?;
+                let (_, format) =
+                    split_extension(&older).context("Backup has no save extension")?;
+                self.write_blob(&backup_base(slot, n + 1), format, &older_data)?;
+            }
+        }
+        let (_, format) = split_extension(&current).context("Save has no save extension")?;
+        self.write_blob(&backup_base(slot, 1), format, &data)
+    }
+}
+
+/// Splits a blob name into its base and the [`SaveFormat`] its extension belongs to.
+fn split_extension(name: &str) -> Option<(&str, SaveFormat)> {
+    SAVE_FORMATS.iter().find_map(|format| {
+        name.strip_suffix(format.extension())
+            .map(|base| (base, *format))
+    })
+}
+
+/// `n` starts at 1 for the newest backup.
+fn backup_base(slot: &str, n: usize) -> String {
+    format!("{BACKUP_DIR}/{slot}.{n}")
+}
+
+/// Prepends a checksum of `body`. The checksum is written as a RON comment, so RON saves stay valid RON.
+pub fn seal_save(body: &[u8]) -> Vec<u8> {
+    let mut sealed = format!("{CHECKSUM_PREFIX}{:08x}\n", crc32fast::hash(body)).into_bytes();
+    sealed.extend_from_slice(body);
+    sealed
+}
+
+/// Whether `data` starts with a checksum added by [`seal_save`].
+pub fn is_save_sealed(data: &[u8]) -> bool {
+    data.starts_with(CHECKSUM_PREFIX.as_bytes())
+}
+
+/// Verifies and strips the checksum added by [`seal_save`]. Saves written before checksums were introduced are
+/// returned unchanged.
+pub fn unseal_save(data: &[u8]) -> Result<&[u8]> {
+    let Some(rest) = data.strip_prefix(CHECKSUM_PREFIX.as_bytes()) else {
+        return Ok(data);
+    };
+    let newline = rest
+        .iter()
+        .position(|byte| *byte == b'\n')
+        .context("Save checksum is not terminated by a newline")?;
+    let (checksum, body) = (&rest[..newline], &rest[newline + 1..]);
+    let checksum = std::str::from_utf8(checksum)
+        .ok()
+        .and_then(|checksum| u32::from_str_radix(checksum.trim(), 16).ok())
+        .context("Save checksum is malformed")?;
+    let actual = crc32fast::hash(body);
+    ensure!(
+        checksum == actual,
+        "Save checksum mismatch: expected {checksum:08x}, got {actual:08x}"
+    );
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_system_interaction::game_state_serialization::CURRENT_SAVE_VERSION;
+    use crate::file_system_interaction::storage::MemoryStorage;
+
+    const SLOT: &str = "slot";
+
+    fn save(scene: &str) -> Vec<u8> {
+        SaveFormat::Ron
+            .serialize(&SaveModel {
+                version: CURRENT_SAVE_VERSION,
+                scene: scene.to_owned(),
+                ..default()
+            })
+            .unwrap()
+    }
+
+    fn read_scene(saves: &SaveStorage) -> Result<String> {
+        saves
+            .read(SLOT, &SaveMigrations::default())
+            .map(|save_model| save_model.scene)
+    }
+
+    /// The scene stored in a blob, bypassing the fallback to backups.
+    fn blob_scene(storage: &MemoryStorage, name: &str) -> Option<String> {
+        let data = storage.read(name).ok()?;
+        let save_model: SaveModel = SaveFormat::Ron.deserialize(unseal_save(&data).ok()?).ok()?;
+        Some(save_model.scene)
+    }
+
+    /// Flips a byte of the body, so the checksum no longer matches.
+    fn corrupt(storage: &MemoryStorage, name: &str) {
+        let mut data = storage.read(name).unwrap();
+        *data.last_mut().unwrap() ^= 1;
+        storage.write(name, &data).unwrap();
+    }
+
+    fn slot_blob() -> String {
+        format!("{SLOT}{}", SaveFormat::Ron.extension())
+    }
+
+    fn backup_blob(n: usize) -> String {
+        format!("{}{}", backup_base(SLOT, n), SaveFormat::Ron.extension())
+    }
+
+    #[test]
+    fn reads_what_was_written() {
+        let saves = SaveStorage::new(MemoryStorage::default(), 3);
+        saves.write(SLOT, &save("first")).unwrap();
+        saves.write(SLOT, &save("second")).unwrap();
+        assert_eq!(read_scene(&saves).unwrap(), "second");
+        assert_eq!(saves.slot_ids().unwrap(), [SLOT]);
+    }
+
+    #[test]
+    fn checksum_mismatch_falls_back_to_newest_backup() {
+        let storage = MemoryStorage::default();
+        let saves = SaveStorage::new(storage.clone(), 3);
+        for scene in ["first", "second", "third"] {
+            saves.write(SLOT, &save(scene)).unwrap();
+        }
+        corrupt(&storage, &slot_blob());
+        let error = unseal_save(&storage.read(&slot_blob()).unwrap()).unwrap_err();
+        assert!(error.to_string().contains("checksum mismatch"), "{error}");
+
+        assert_eq!(read_scene(&saves).unwrap(), "second");
+    }
+
+    #[test]
+    fn skips_corrupted_backups() {
+        let storage = MemoryStorage::default();
+        let saves = SaveStorage::new(storage.clone(), 3);
+        for scene in ["first", "second", "third"] {
+            saves.write(SLOT, &save(scene)).unwrap();
+        }
+        corrupt(&storage, &slot_blob());
+        corrupt(&storage, &backup_blob(1));
+
+        assert_eq!(read_scene(&saves).unwrap(), "first");
+    }
+
+    #[test]
+    fn fails_if_save_and_all_backups_are_corrupted() {
+        let storage = MemoryStorage::default();
+        let saves = SaveStorage::new(storage.clone(), 3);
+        for scene in ["first", "second", "third"] {
+            saves.write(SLOT, &save(scene)).unwrap();
+        }
+        for name in [slot_blob(), backup_blob(1), backup_blob(2)] {
+            corrupt(&storage, &name);
+        }
+
+        let error = read_scene(&saves).unwrap_err().to_string();
+        assert!(
+            error.contains("Neither save \"slot\" nor any of its backups could be read"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn missing_slot_is_an_error() {
+        let saves = SaveStorage::new(MemoryStorage::default(), 3);
+        let error = read_scene(&saves).unwrap_err().to_string();
+        assert!(error.contains("No such save: slot"), "{error}");
+    }
+
+    #[test]
+    fn rotation_keeps_the_configured_number_of_backups() {
+        let storage = MemoryStorage::default();
+        let saves = SaveStorage::new(storage.clone(), 3);
+        for scene in ["1", "2", "3", "4", "5"] {
+            saves.write(SLOT, &save(scene)).unwrap();
+        }
+
+        assert_eq!(blob_scene(&storage, &slot_blob()).as_deref(), Some("5"));
+        assert_eq!(blob_scene(&storage, &backup_blob(1)).as_deref(), Some("4"));
+        assert_eq!(blob_scene(&storage, &backup_blob(2)).as_deref(), Some("3"));
+        assert_eq!(blob_scene(&storage, &backup_blob(3)).as_deref(), Some("2"));
+        assert!(!storage.exists(&backup_blob(4)));
+        assert_eq!(storage.list().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn rotation_does_not_back_up_corrupted_saves() {
+        let storage = MemoryStorage::default();
+        let saves = SaveStorage::new(storage.clone(), 3);
+        for scene in ["1", "2", "3"] {
+            saves.write(SLOT, &save(scene)).unwrap();
+        }
+        corrupt(&storage, &slot_blob());
+        saves.write(SLOT, &save("4")).unwrap();
+
+        assert_eq!(blob_scene(&storage, &slot_blob()).as_deref(), Some("4"));
+        // The corrupted "3" was dropped instead of pushing the older backups back
+        assert_eq!(blob_scene(&storage, &backup_blob(1)).as_deref(), Some("2"));
+        assert_eq!(blob_scene(&storage, &backup_blob(2)).as_deref(), Some("1"));
+        assert!(!storage.exists(&backup_blob(3)));
+    }
+
+    #[test]
+    fn switching_format_replaces_the_old_blob() {
+        let storage = MemoryStorage::default();
+        let saves = SaveStorage::new(storage.clone(), 3);
+        saves.write(SLOT, &save("ron")).unwrap();
+        let binary = SaveFormat::Binary
+            .serialize(&SaveModel {
+                version: CURRENT_SAVE_VERSION,
+                scene: "binary".to_owned(),
+                ..default()
+            })
+            .unwrap();
+        saves.write(SLOT, &binary).unwrap();
+
+        assert!(!storage.exists(&slot_blob()));
+        assert!(storage.exists(&format!("{SLOT}{}", SaveFormat::Binary.extension())));
+        assert_eq!(read_scene(&saves).unwrap(), "binary");
+        assert_eq!(
+            blob_scene(&storage, &backup_blob(1)).as_deref(),
+            Some("ron")
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use crate::file_system_interaction::game_state_serialization::{SaveMigrations, SaveStorage};
+use crate::GameState;
+use anyhow::Result;
+use bevy::prelude::*;
+use bevy_mod_sysfail::macros::*;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub(super) fn save_slots_plugin(app: &mut App) {
+    app.init_resource::<SaveSlots>()
+        .init_resource::<Playtime>()
+        .add_event::<GameDeleteRequest>()
+        .add_system(refresh_save_slots.in_schedule(OnEnter(GameState::Playing)))
+        .add_systems((track_playtime, handle_delete_requests).in_set(OnUpdate(GameState::Playing)));
+}
+
+/// Information about a save that is shown to the player when choosing which save to load.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct SaveMetadata {
+    /// Chosen by the player. Empty if the player did not name the save.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub label: String,
+    #[serde(default)]
+    pub playtime: Duration,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub objective: Option<String>,
+}
+
+/// A save on disk, as listed in the save browser.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SaveSlot {
+    /// The name of the save file without extension. Used to address the slot in [`GameSaveRequest`](super::GameSaveRequest),
+    /// [`GameLoadRequest`](super::GameLoadRequest) and [`GameDeleteRequest`].
+    pub id: String,
+    pub level: String,
+    pub metadata: SaveMetadata,
+    /// Falls back to the storage's modification time for saves written before timestamps were recorded.
+    pub timestamp: DateTime<Utc>,
+}
+
+impl SaveSlot {
+    pub fn display_label(&self) -> &str {
+        if self.metadata.label.is_empty() {
+            &self.id
+        } else {
+            &self.metadata.label
+        }
+    }
+}
+
+/// All saves on disk, newest first. Kept up to date when saving or deleting through the save requests.
+#[derive(Debug, Clone, PartialEq, Resource, Default, Deref)]
+pub struct SaveSlots(Vec<SaveSlot>);
+
+impl SaveSlots {
+    pub fn get(&self, id: &str) -> Option<&SaveSlot> {
+        self.0.iter().find(|slot| slot.id == id)
+    }
+
+    pub fn newest(&self) -> Option<&SaveSlot> {
+        self.0.first()
+    }
+
+    pub fn refresh(&mut self, storage: &SaveStorage, migrations: &SaveMigrations) -> Result<()> {
+        self.0 = list_save_slots(storage, migrations)?;
+        Ok(())
+    }
+}
+
+/// Reads all saves in the storage, newest first. Saves that cannot be read are skipped with an error message.
+pub fn list_save_slots(
+    storage: &SaveStorage,
+    migrations: &SaveMigrations,
+) -> Result<Vec<SaveSlot>> {
+    let mut slots: Vec<_> = storage
+        .slot_ids()?
+        .into_iter()
+        .filter_map(|id| match read_save_slot(id.clone(), storage, migrations) {
+            Ok(slot) => Some(slot),
+            Err(e) => {
+                error!("Failed to read save slot \"{id}\": {e:#}");
+                None
+            }
+        })
+        .collect();
+    slots.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    Ok(slots)
+}
+
+fn read_save_slot(
+    id: String,
+    storage: &SaveStorage,
+    migrations: &SaveMigrations,
+) -> Result<SaveSlot> {
+    let save_model = storage.read(&id, migrations)?;
+    let modified = storage.modified(&id).map(DateTime::<Utc>::from);
+    Ok(SaveSlot {
+        timestamp: save_model
+            .metadata
+            .timestamp
+            .or(modified)
+            .unwrap_or_default(),
+        level: save_model.scene,
+        metadata: save_model.metadata,
+        id,
+    })
+}
+
+/// Total time spent in [`GameState::Playing`] while the game was not paused. Carried over between saves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Resource, Default, Deref, DerefMut)]
+pub struct Playtime(pub Duration);
+
+fn track_playtime(time: Res<Time>, mut playtime: ResMut<Playtime>) {
+    playtime.0 += time.delta();
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, Default)]
+pub struct GameDeleteRequest {
+    pub slot: String,
+}
+
+#[sysfail(log(level = "error"))]
+pub(super) fn refresh_save_slots(
+    mut save_slots: ResMut<SaveSlots>,
+    storage: Res<SaveStorage>,
+    migrations: Res<SaveMigrations>,
+) -> Result<()> {
+    save_slots.refresh(&storage, &migrations)
+}
+
+#[sysfail(log(level = "error"))]
+fn handle_delete_requests(
+    mut delete_events: EventReader<GameDeleteRequest>,
+    mut save_slots: ResMut<SaveSlots>,
+    storage: Res<SaveStorage>,
+    migrations: Res<SaveMigrations>,
+) -> Result<()> {
+    let mut deleted_any = false;
+    for delete in delete_events.iter() {
+        match storage.delete(&delete.slot) {
+            Ok(()) => {
+                info!("Successfully deleted save \"{}\"", delete.slot);
+                deleted_any = true;
+            }
+            Err(e) => error!("Failed to delete save \"{}\": {:#}", delete.slot, e),
+        }
+    }
+    if deleted_any {
+        save_slots.refresh(&storage, &migrations)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,497 @@
+use crate::file_system_interaction::level_serialization::{CurrentLevel, LevelObject};
+use crate::level_instantiation::spawning::{
+    GameObject, GameObjectRegistry, ObjectProperties, PersistentId, PersistentIdLookup,
+    PersistentIdLookupUpdate, SpawnData,
+};
+use crate::level_instantiation::streaming::{
+    LevelStreaming, LevelStreamingSystemSet, UnloadedObject,
+};
+use crate::movement::general_movement::Walking;
+use crate::player_control::camera::{CameraState, IngameCamera};
+use crate::player_control::player_embodiment::Player;
+use crate::util::trait_extension::TransformExt;
+use crate::world_interaction::condition::ActiveConditions;
+use crate::world_interaction::dialog::DialogEvent;
+use crate::GameState;
+use anyhow::{Context, Result};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_dolly::prelude::Rig;
+use bevy_mod_sysfail::macros::*;
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+use spew::prelude::*;
+
+pub(super) fn world_delta_plugin(app: &mut App) {
+    app.init_resource::<PendingMotions>().add_systems(
+        (
+            // Runs first so that it only sees motions requested in earlier frames, whose objects are spawned by now
+            apply_pending_motions.run_if(has_pending_motions),
+            apply_pending_load
+                .run_if(pending_level_loaded.and_then(any_with_component::<Player>())),
+        )
+            .chain()
+            .after(PersistentIdLookupUpdate)
+            // Changes to objects in unloaded cells are applied before the cells around the player are loaded
+            .before(LevelStreamingSystemSet)
+            .in_set(OnUpdate(GameState::Playing)),
+    );
+}
+
+/// Everything about the world that differs from the level file it was loaded from.
+/// Objects are referenced by their [`PersistentId`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct WorldDelta {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed: Vec<PersistentId>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub changed: Vec<ChangedObject>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub added: Vec<AddedObject>,
+}
+
+impl WorldDelta {
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty() && self.changed.is_empty() && self.added.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChangedObject {
+    pub id: PersistentId,
+    pub transform: Transform,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub motion: Option<CharacterMotion>,
+    /// Empty if the properties are the same as in the level file.
+    #[serde(default, skip_serializing_if = "ObjectProperties::is_empty")]
+    pub properties: ObjectProperties,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AddedObject {
+    pub id: PersistentId,
+    pub object: GameObject,
+    pub transform: Transform,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub motion: Option<CharacterMotion>,
+    #[serde(default, skip_serializing_if = "ObjectProperties::is_empty")]
+    pub properties: ObjectProperties,
+}
+
+/// Movement state of a character controller such as an NPC.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CharacterMotion {
+    pub velocity: Velocity,
+    pub walking: Walking,
+}
+
+impl CharacterMotion {
+    /// Returns `None` for objects that are not character controllers.
+    pub fn read(velocity: Option<&Velocity>, walking: Option<&Walking>) -> Option<Self> {
+        Some(Self {
+            velocity: *velocity?,
+            walking: walking?.clone(),
+        })
+    }
+}
+
+/// State from a save that can only be applied once the level it belongs to has been spawned.
+#[derive(Debug, Clone, PartialEq, Resource)]
+pub(super) struct PendingLoad {
+    pub(super) conditions: ActiveConditions,
+    pub(super) camera: Option<CameraState>,
+    pub(super) world: WorldDelta,
+    pub(super) player_transform: Transform,
+    pub(super) dialog_event: Option<DialogEvent>,
+    /// Set once the level of the save has been spawned, which may take a while if its assets need to be loaded.
+    pub(super) level_loaded: bool,
+}
+
+fn pending_level_loaded(pending_load: Option<Res<PendingLoad>>) -> bool {
+    pending_load.map_or(false, |pending_load| pending_load.level_loaded)
+}
+
+/// Motion of objects that have been requested but not spawned yet, e.g. from a [`WorldDelta`] or a
+/// streamed cell. Applied in the frame after the objects were requested.
+#[derive(Debug, Clone, PartialEq, Resource, Default)]
+pub struct PendingMotions(HashMap<PersistentId, CharacterMotion>);
+
+impl PendingMotions {
+    pub fn insert(&mut self, id: PersistentId, motion: CharacterMotion) {
+        self.0.insert(id, motion);
+    }
+}
+
+fn has_pending_motions(pending_motions: Res<PendingMotions>) -> bool {
+    !pending_motions.0.is_empty()
+}
+
+/// The live objects a [`WorldDelta`] is computed from.
+#[derive(SystemParam)]
+pub(super) struct DeltaObjects<'w, 's> {
+    /// Properties can only be read from an entity as a whole.
+    world: &'w World,
+    objects: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static PersistentId,
+            &'static GameObject,
+            &'static Transform,
+            Option<&'static Velocity>,
+            Option<&'static Walking>,
+        ),
+    >,
+    registry: Res<'w, GameObjectRegistry>,
+    streaming: Option<Res<'w, LevelStreaming>>,
+}
+
+/// `level` are the objects the current level was spawned with, including the ones of prefab instances.
+pub(super) fn compute_world_delta(level: &[LevelObject], objects: &DeltaObjects) -> WorldDelta {
+    let DeltaObjects {
+        world,
+        objects,
+        registry,
+        streaming,
+    } = objects;
+    // Objects in unloaded cells are still part of the world, they just don't move
+    let unloaded_objects = streaming
+        .iter()
+        .flat_map(|streaming| streaming.unloaded_objects())
+        .filter(|unloaded| registry.is_saved_in_saves(&unloaded.object.object))
+        .map(|unloaded| {
+            (
+                &unloaded.object.id,
+                &unloaded.object.object,
+                &unloaded.object.transform,
+                unloaded.motion.clone(),
+                unloaded.object.properties.clone(),
+            )
+        });
+    let live_objects: Vec<_> = objects
+        .iter()
+        .filter_map(|(entity, id, object, transform, velocity, walking)| {
+            let registration = registry
+                .get(object)
+                .filter(|registration| registration.saved_in_saves)?;
+            let properties = (registration.read_properties)(world.entity(entity));
+            Some((
+                id,
+                object,
+                transform,
+                CharacterMotion::read(velocity, walking),
+                properties,
+            ))
+        })
+        .chain(unloaded_objects)
+        .collect();
+    let level_objects: HashMap<_, _> = level
+        .iter()
+        .filter(|entry| registry.is_saved_in_saves(&entry.object))
+        .map(|entry| (entry.id, entry))
+        .collect();
+    let live_ids: HashMap<_, _> = live_objects
+        .iter()
+        .map(|(id, object, ..)| (**id, *object))
+        .collect();
+    // Level files and property readers may leave out properties that have their default value
+    let with_defaults =
+        |object: &GameObject, properties: &ObjectProperties| match registry.get(object) {
+            Some(registration) => properties
+                .clone()
+                .with_defaults(&registration.default_properties),
+            None => properties.clone(),
+        };
+
+    let mut delta = WorldDelta {
+        removed: level_objects
+            .values()
+            .filter(|entry| live_ids.get(&entry.id) != Some(&&entry.object))
+            .map(|entry| entry.id)
+            .collect(),
+        ..default()
+    };
+    for (id, object, transform, motion, properties) in live_objects {
+        match level_objects.get(id) {
+            Some(entry) if entry.object == *object => {
+                let properties_changed =
+                    with_defaults(object, &properties) != with_defaults(object, &entry.properties);
+                if motion.is_some()
+                    || properties_changed
+                    || !transform.is_approx_eq(entry.transform)
+                {
+                    delta.changed.push(ChangedObject {
+                        id: *id,
+                        transform: *transform,
+                        motion,
+                        properties: if properties_changed {
+                            properties
+                        } else {
+                            default()
+                        },
+                    });
+                }
+            }
+            _ => delta.added.push(AddedObject {
+                id: *id,
+                object: object.clone(),
+                transform: *transform,
+                motion,
+                properties,
+            }),
+        }
+    }
+    delta
+}
+
+#[sysfail(log(level = "error"))]
+fn apply_pending_load(
+    mut commands: Commands,
+    pending_load: Res<PendingLoad>,
+    lookup: Res<PersistentIdLookup>,
+    mut motion_query: Query<(&mut Transform, Option<&mut Velocity>, Option<&mut Walking>)>,
+    object_query: Query<&GameObject>,
+    mut spawner: EventWriter<SpawnEvent<GameObject, SpawnData>>,
+    mut camera_query: Query<(&mut IngameCamera, &mut Rig)>,
+    current_level: Res<CurrentLevel>,
+    registry: Res<GameObjectRegistry>,
+    mut streaming: Option<ResMut<LevelStreaming>>,
+    mut pending_motions: ResMut<PendingMotions>,
+) -> Result<()> {
+    commands.remove_resource::<PendingLoad>();
+    commands.insert_resource(pending_load.conditions.clone());
+    if let Some(camera_state) = &pending_load.camera {
+        // The camera was respawned along with the level
+        for (mut camera, mut rig) in camera_query.iter_mut() {
+            camera_state.apply(&mut camera, &mut rig);
+        }
+    }
+
+    let get_entity = |id: PersistentId| {
+        lookup.get(id).with_context(|| {
+            format!(
+                "Saved world references object {id:?}, which does not exist in level \"{}\"",
+                current_level.scene
+            )
+        })
+    };
+
+    let delta = &pending_load.world;
+    for id in delta.removed.iter() {
+        if let Some(streaming) = &mut streaming && streaming.take(*id).is_some() {
+            continue;
+        }
+        match get_entity(*id) {
+            Ok(entity) => commands.entity(entity).despawn_recursive(),
+            Err(e) => warn!("{e}"),
+        }
+    }
+    for changed in delta.changed.iter() {
+        if let Some(streaming) = &mut streaming
+            && let Some(mut unloaded) = streaming.take(changed.id)
+        {
+            unloaded.object.transform = changed.transform;
+            if !changed.properties.is_empty() {
+                unloaded.object.properties = changed.properties.clone();
+            }
+            unloaded.motion = changed.motion.clone();
+            // The object may have been saved in a cell that is loaded already
+            if let Some(unloaded) = streaming.store(unloaded)
+                && let Some(event) = unloaded.object.spawn_event(&registry)
+            {
+                spawner.send(event);
+                if let Some(motion) = unloaded.motion {
+                    pending_motions.insert(changed.id, motion);
+                }
+            }
+            continue;
+        }
+        let entity = match get_entity(changed.id) {
+            Ok(entity) => entity,
+            Err(e) => {
+                warn!("{e}");
+                continue;
+            }
+        };
+        if !changed.properties.is_empty() {
+            // Properties are only read by spawners, so the object is spawned again with the saved ones
+            let object = object_query
+                .get(entity)
+                .context("Failed to get type of level object")?;
+            let data = SpawnData::new(changed.transform, changed.id)
+                .with_properties(changed.properties.clone());
+            if let Some(event) = registry.spawn_event(object, data) {
+                commands.entity(entity).despawn_recursive();
+                spawner.send(event);
+                if let Some(motion) = &changed.motion {
+                    pending_motions.insert(changed.id, motion.clone());
+                }
+                continue;
+            }
+        }
+        let (mut transform, velocity, walking) = motion_query
+            .get_mut(entity)
+            .context("Failed to get transform of level object")?;
+        *transform = changed.transform;
+        if let Some(motion) = &changed.motion {
+            apply_motion(motion, velocity, walking);
+        }
+    }
+    for added in delta.added.iter() {
+        if let Some(streaming) = &mut streaming && registry.is_streamed(&added.object) {
+            let unloaded = UnloadedObject {
+                object: LevelObject {
+                    id: added.id,
+                    object: added.object.clone(),
+                    transform: added.transform,
+                    properties: added.properties.clone(),
+                },
+                motion: added.motion.clone(),
+            };
+            if streaming.store(unloaded).is_none() {
+                continue;
+            }
+        }
+        let data =
+            SpawnData::new(added.transform, added.id).with_properties(added.properties.clone());
+        let Some(event) = registry.spawn_event(&added.object, data) else {
+            warn!(
+                "Saved world contains object {:?} of unknown type \"{}\"",
+                added.id, added.object
+            );
+            continue;
+        };
+        spawner.send(event);
+        if let Some(motion) = &added.motion {
+            pending_motions.insert(added.id, motion.clone());
+        }
+    }
+    Ok(())
+}
+
+fn apply_pending_motions(
+    mut pending_motions: ResMut<PendingMotions>,
+    lookup: Res<PersistentIdLookup>,
+    mut motion_query: Query<(Option<&mut Velocity>, Option<&mut Walking>)>,
+) {
+    for (id, motion) in pending_motions.0.drain() {
+        match lookup
+            .get(id)
+            .and_then(|entity| motion_query.get_mut(entity).ok())
+        {
+            Some((velocity, walking)) => apply_motion(&motion, velocity, walking),
+            None => {
+                warn!("Failed to restore motion of saved object {id:?}: No such object was spawned")
+            }
+        }
+    }
+}
+
+fn apply_motion(
+    motion: &CharacterMotion,
+    velocity: Option<Mut<Velocity>>,
+    walking: Option<Mut<Walking>>,
+) {
+    if let Some(mut velocity) = velocity {
+        *velocity = motion.velocity;
+    }
+    if let Some(mut walking) = walking {
+        *walking = motion.walking.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level_instantiation::spawning::objects::orb::DEFAULT_GLOW_COLOR;
+    use crate::level_instantiation::spawning::{builtin_object_registry, GlowColor};
+    use bevy::ecs::system::SystemState;
+
+    const ORB_ID: PersistentId = PersistentId(1);
+
+    fn world_with_orb(glow_color: Color) -> World {
+        let mut world = World::new();
+        world.insert_resource(builtin_object_registry());
+        world
+            .spawn((ORB_ID, GameObject::ORB, Transform::default()))
+            .with_children(|parent| {
+                parent.spawn(PointLight {
+                    color: glow_color,
+                    ..default()
+                });
+            });
+        world
+    }
+
+    fn level_orb(properties: ObjectProperties) -> LevelObject {
+        LevelObject {
+            id: ORB_ID,
+            object: GameObject::ORB,
+            transform: Transform::default(),
+            properties,
+        }
+    }
+
+    fn compute(world: &mut World, level: &[LevelObject]) -> WorldDelta {
+        let mut state = SystemState::<DeltaObjects>::new(world);
+        compute_world_delta(level, &state.get(world))
+    }
+
+    #[test]
+    fn unchanged_default_properties_are_not_saved() {
+        let mut world = world_with_orb(DEFAULT_GLOW_COLOR);
+        assert!(compute(&mut world, &[level_orb(default())]).is_empty());
+    }
+
+    #[test]
+    fn changed_properties_are_saved() {
+        let mut world = world_with_orb(Color::BLUE);
+        let level = [level_orb(
+            ObjectProperties::default().with(GlowColor(Color::RED)),
+        )];
+        let delta = compute(&mut world, &level);
+        assert_eq!(delta.changed.len(), 1);
+        assert_eq!(
+            delta.changed[0].properties.get::<GlowColor>(),
+            Some(&GlowColor(Color::BLUE))
+        );
+    }
+
+    #[test]
+    fn added_objects_keep_their_properties() {
+        let mut world = world_with_orb(Color::BLUE);
+        let delta = compute(&mut world, &[]);
+        assert_eq!(delta.added.len(), 1);
+        assert_eq!(
+            delta.added[0].properties.get::<GlowColor>(),
+            Some(&GlowColor(Color::BLUE))
+        );
+    }
+
+    #[test]
+    fn properties_round_trip() {
+        builtin_object_registry();
+        let delta = WorldDelta {
+            changed: vec![ChangedObject {
+                id: ORB_ID,
+                transform: default(),
+                motion: None,
+                properties: ObjectProperties::default().with(GlowColor(Color::BLUE)),
+            }],
+            ..default()
+        };
+        let serialized = ron::to_string(&delta).unwrap();
+        assert_eq!(ron::from_str::<WorldDelta>(&serialized).unwrap(), delta);
+    }
+
+    #[test]
+    fn objects_saved_without_properties_can_be_read() {
+        let changed: ChangedObject = ron::from_str(
+            "(id: 1, transform: (translation: (0.0, 0.0, 0.0), rotation: (0.0, 0.0, 0.0, 1.0), scale: (1.0, 1.0, 1.0)))",
+        )
+        .unwrap();
+        assert!(changed.properties.is_empty());
+    }
+}
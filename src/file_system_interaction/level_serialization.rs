@@ -1,26 +1,87 @@
-use crate::file_system_interaction::asset_loading::LevelAssets;
-use crate::level_instantiation::spawning::GameObject;
+use crate::file_system_interaction::asset_loading::{
+    AssetIndex, CharacterAssets, LevelAssets, NamedAssets, PrefabAssets,
+};
+use crate::file_system_interaction::storage::{Storage, StorageBackend, StorageLocation};
+use crate::level_instantiation::spawning::{
+    get_character_asset_path, GameObject, GameObjectRegistry, ObjectProperties, PersistentId,
+    SpawnData,
+};
+use crate::level_instantiation::streaming::{LevelStreaming, StreamingSettings};
+use crate::movement::navigation::nav_mesh_settings;
+use crate::player_control::player_embodiment::Player;
 use crate::world_interaction::condition::ActiveConditions;
-use crate::world_interaction::dialog::CurrentDialog;
+use crate::world_interaction::dialog::{get_dialog_asset_path, CurrentDialog};
 use crate::world_interaction::interactions_ui::InteractionOpportunities;
-use anyhow::{Context, Result};
+use anyhow::{ensure, Context, Result};
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy::reflect::TypeUuid;
+use bevy::utils::HashSet;
 use bevy_mod_sysfail::macros::*;
+use bevy_rapier3d::prelude::Velocity;
+use oxidized_navigation::{NavMesh, NavMeshSettings};
 use serde::{Deserialize, Serialize};
 use spew::prelude::*;
-use std::path::Path;
-use std::{fs, iter};
+use std::sync::Arc;
+
+mod diff;
+mod history;
+mod hot_reload;
+mod level_assets;
+mod prefab;
+mod validation;
+pub use diff::LevelDiff;
+use history::back_up_level;
+pub use history::level_history;
+use hot_reload::{apply_level_reload, queue_level_reload, PendingLevelReload};
+use level_assets::{start_level_loading, track_level_loading, LevelAssetsLoaded, PendingLevelLoad};
+pub use level_assets::{LevelAssetList, LevelLoadingProgress};
+pub use prefab::{
+    get_prefab_asset_path, get_prefab_file_name, ExpandedLevel, Prefab, PrefabInstance,
+    PropertyOverride,
+};
+pub use validation::{format_level_problems, LevelProblem, LevelValidator};
 
 pub fn level_serialization_plugin(app: &mut App) {
-    app.add_event::<WorldSaveRequest>()
+    app.init_resource::<LevelStorage>()
+        .init_resource::<LevelHistoryStorage>()
+        .add_event::<WorldSaveRequest>()
         .add_event::<WorldLoadRequest>()
+        .add_event::<LevelAssetsLoaded>()
+        .add_event::<WorldLoadFinished>()
+        .add_systems(
+            (
+                start_level_loading,
+                track_level_loading.run_if(
+                    resource_exists::<PendingLevelLoad>()
+                        .and_then(resource_exists::<PrefabAssets>())
+                        .and_then(resource_exists::<NamedAssets>()),
+                ),
+            )
+                .chain()
+                .before(load_world)
+                .in_base_set(CoreSet::PostUpdate),
+        )
         .add_systems(
             (
                 save_world,
-                load_world.run_if(resource_exists::<LevelAssets>()),
+                load_world.run_if(
+                    resource_exists::<PrefabAssets>()
+                        .and_then(resource_exists::<CharacterAssets>()),
+                ),
             )
                 .in_base_set(CoreSet::PostUpdate),
+        )
+        .add_systems(
+            (
+                queue_level_reload.run_if(
+                    resource_exists::<LevelAssets>().and_then(resource_exists::<PrefabAssets>()),
+                ),
+                apply_level_reload.run_if(resource_exists::<PendingLevelReload>()),
+            )
+                .chain()
+                .after(load_world)
+                .in_base_set(CoreSet::PostUpdate),
         );
 }
 
@@ -28,12 +89,28 @@ pub fn level_serialization_plugin(app: &mut App) {
 #[reflect(Serialize, Deserialize)]
 pub struct WorldSaveRequest {
     pub filename: String,
+    /// Replaces an existing level of the same name, keeping the previous version in the level history.
+    /// Without this, saving fails if the level already exists.
+    #[serde(default)]
+    pub overwrite: bool,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Reflect, Serialize, Deserialize, Default)]
 #[reflect(Serialize, Deserialize)]
 pub struct WorldLoadRequest {
     pub filename: String,
+    /// The name of the spawn point the player arrives at. `None` leaves placing the player to the sender,
+    /// e.g. when loading a save.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spawn_point: Option<String>,
+}
+
+/// Sent once a [`WorldLoadRequest`] has been handled, after the assets of the level were loaded.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct WorldLoadFinished {
+    pub request: WorldLoadRequest,
+    /// Whether the level was spawned. On failure, the previous level is left as it is.
+    pub success: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Resource, Reflect, Serialize, Deserialize, Default)]
@@ -42,92 +119,261 @@ pub struct CurrentLevel {
     pub scene: String,
 }
 
+/// The level that was last loaded through a [`WorldLoadRequest`].
+#[derive(Debug, Clone, PartialEq, Resource)]
+pub struct LoadedLevel {
+    /// The level as stored in its file.
+    pub level: SerializedLevel,
+    pub expanded: ExpandedLevel,
+}
+
+/// Where levels are written to by [`WorldSaveRequest`]. The underlying [`Storage`] is chosen by [`StorageBackend`].
+#[derive(Clone, Resource, Deref)]
+pub struct LevelStorage(pub Arc<dyn Storage>);
+
+impl FromWorld for LevelStorage {
+    fn from_world(world: &mut World) -> Self {
+        let backend = *world.get_resource_or_insert_with(StorageBackend::default);
+        Self(backend.open_or_memory(StorageLocation::Levels))
+    }
+}
+
+/// Where [`WorldSaveRequest`]s keep the previous versions of the levels they overwrite, see [`level_history`].
+#[derive(Clone, Resource, Deref)]
+pub struct LevelHistoryStorage(pub Arc<dyn Storage>);
+
+impl FromWorld for LevelHistoryStorage {
+    fn from_world(world: &mut World) -> Self {
+        let backend = *world.get_resource_or_insert_with(StorageBackend::default);
+        Self(backend.open_or_memory(StorageLocation::LevelHistory))
+    }
+}
+
 #[sysfail(log(level = "error"))]
 fn save_world(
+    world: &World,
     mut save_requests: EventReader<WorldSaveRequest>,
-    spawn_query: Query<(&GameObject, Option<&Transform>)>,
+    spawn_query: LevelObjectQuery,
+    registry: Res<GameObjectRegistry>,
+    loaded_level: Option<Res<LoadedLevel>>,
+    storage: Res<LevelStorage>,
+    history_storage: Res<LevelHistoryStorage>,
 ) -> Result<()> {
     for save in save_requests.iter() {
-        let scene = save.filename.clone();
-        let valid_candidates: Vec<_> = iter::once(scene.clone())
-            .chain((1..).map(|n| format!("{0}-{n}", scene.clone())))
-            .map(|filename| {
-                Path::new("assets")
-                    .join("levels")
-                    .join(filename)
-                    .with_extension("lvl.ron")
-            })
-            .map(|path| (path.clone(), fs::try_exists(path).ok()))
-            .take(10)
-            .filter_map(|(path, maybe_exists)| maybe_exists.map(|exists| (path, exists)))
-            .collect();
-        if valid_candidates.is_empty() {
-            error!("Failed to save scene \"{}\": Invalid path", scene);
-        } else if let Some(path) = valid_candidates
-            .iter()
-            .filter_map(|(path, exists)| (!exists).then_some(path))
-            .next()
-        {
-            let serialized_world = serialize_world(&spawn_query)?;
-            let dir = path.parent().context("Failed to get level directory")?;
-            fs::create_dir_all(dir).context("Failed to create level directory")?;
-            fs::write(path, serialized_world)
-                .unwrap_or_else(|e| error!("Failed to save level \"{}\": {}", scene, e));
-            info!(
-                "Successfully saved level \"{}\" at {}",
-                scene,
-                path.to_string_lossy()
-            );
-        } else {
-            error!(
-                "Failed to save level \"{}\": Already got too many saves with this name",
-                scene
-            );
+        let level = snapshot_level(
+            world,
+            spawn_query.iter(),
+            &registry,
+            loaded_level.as_deref(),
+        );
+        match write_level(
+            storage.0.as_ref(),
+            history_storage.0.as_ref(),
+            &save.filename,
+            &level,
+            save.overwrite,
+        ) {
+            Ok(()) => info!("Successfully saved level \"{}\"", save.filename),
+            Err(e) => error!("Failed to save level \"{}\": {e:#}", save.filename),
         }
     }
     Ok(())
 }
 
+fn write_level(
+    storage: &dyn Storage,
+    history_storage: &dyn Storage,
+    filename: &str,
+    level: &SerializedLevel,
+    overwrite: bool,
+) -> Result<()> {
+    let name = get_level_file_name(filename);
+    if storage.exists(&name) {
+        ensure!(
+            overwrite,
+            "{name} already exists. Save in overwrite mode to replace it"
+        );
+        match read_stored_level(storage, filename) {
+            Ok(Some(old_level)) => info!(
+                "Overwriting level \"{filename}\": {}",
+                LevelDiff::between(&old_level, level)
+            ),
+            Ok(None) => {}
+            Err(e) => warn!("Overwriting level \"{filename}\", which could not be read: {e:#}"),
+        }
+        let backup = back_up_level(storage, history_storage, filename, &name)?;
+        info!("Kept the previous version of level \"{filename}\" as {backup}");
+    }
+    let serialized =
+        ron::ser::to_string_pretty(level, default()).context("Failed to serialize world")?;
+    storage.write(&name, serialized.as_bytes())
+}
+
+/// Reads a level from [`LevelStorage`]. Returns `None` if there is no level with this name.
+pub fn read_stored_level(storage: &dyn Storage, filename: &str) -> Result<Option<SerializedLevel>> {
+    let name = get_level_file_name(filename);
+    if !storage.exists(&name) {
+        return Ok(None);
+    }
+    let data = storage.read(&name)?;
+    let serialized = std::str::from_utf8(&data).with_context(|| format!("{name} is not UTF-8"))?;
+    let level = ron::from_str(serialized).with_context(|| format!("Failed to parse {name}"))?;
+    Ok(Some(level))
+}
+
+/// Compares the current world with the level stored under `filename`, i.e. what saving it in overwrite mode
+/// would change. Returns `None` if there is no such level yet.
+pub fn preview_level_save(world: &mut World, filename: &str) -> Result<Option<LevelDiff>> {
+    let mut query = world.query::<(Entity, &GameObject, Option<&Transform>, &PersistentId)>();
+    let level = snapshot_level(
+        world,
+        query.iter(world),
+        world.resource::<GameObjectRegistry>(),
+        world.get_resource::<LoadedLevel>(),
+    );
+    let storage = world.resource::<LevelStorage>();
+    Ok(read_stored_level(storage.0.as_ref(), filename)?
+        .map(|old_level| LevelDiff::between(&old_level, &level)))
+}
+
+/// Levels and prefabs as loaded by the asset server.
+#[derive(SystemParam)]
+struct LevelSources<'w> {
+    levels: Res<'w, Assets<SerializedLevel>>,
+    level_handles: Res<'w, LevelAssets>,
+    prefabs: Res<'w, Assets<Prefab>>,
+    prefab_handles: Res<'w, PrefabAssets>,
+}
+
+impl LevelSources<'_> {
+    fn get(&self, filename: &str) -> Result<&SerializedLevel> {
+        let path = get_level_asset_path(filename);
+        let handle = self.level_handles.levels.get(&path).with_context(|| {
+            format!(
+                "No such level: {path}. Available levels: {:?}",
+                self.level_handles.levels.keys()
+            )
+        })?;
+        self.levels
+            .get(handle)
+            .context("Failed to get level from handle in level assets")
+    }
+
+    fn expand(&self, level: &SerializedLevel) -> Result<ExpandedLevel> {
+        expand_level(level, &self.prefabs, &self.prefab_handles)
+    }
+}
+
+fn expand_level(
+    level: &SerializedLevel,
+    prefabs: &Assets<Prefab>,
+    prefab_handles: &PrefabAssets,
+) -> Result<ExpandedLevel> {
+    ExpandedLevel::expand(level, |name| {
+        let handle = prefab_handles.prefabs.get(&get_prefab_asset_path(name))?;
+        prefabs.get(handle).map(|prefab| &prefab.0)
+    })
+}
+
+/// Keeps an object alive when another level is loaded, e.g. the player and their followers during a level
+/// transition. Protected objects are moved to the requested spawn point and lose this marker once they arrive.
 #[derive(Debug, Component, Clone, PartialEq, Default, Reflect, Serialize, Deserialize)]
 #[reflect(Component, Serialize, Deserialize)]
 pub struct Protected;
 
+/// Used when a level has no spawn point with the requested name.
+const FALLBACK_SPAWN_TRANSFORM: Transform = Transform::from_xyz(0., 1.5, 0.);
+/// Distance between followers lining up behind the player at a spawn point.
+const FOLLOWER_SPACING: f32 = 1.5;
+
+type ProtectedQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static PersistentId,
+        &'static mut Transform,
+        Option<&'static mut Velocity>,
+        Option<&'static Player>,
+    ),
+    With<Protected>,
+>;
+
 #[sysfail(log(level = "error"))]
 fn load_world(
     mut commands: Commands,
-    mut load_requests: EventReader<WorldLoadRequest>,
-    current_spawn_query: Query<Entity, With<GameObject>>,
-    mut spawn_requests: EventWriter<SpawnEvent<GameObject, Transform>>,
-    levels: Res<Assets<SerializedLevel>>,
-    level_handles: Res<LevelAssets>,
+    mut load_requests: EventReader<LevelAssetsLoaded>,
+    mut finished_events: EventWriter<WorldLoadFinished>,
+    current_spawn_query: Query<Entity, (With<GameObject>, Without<Protected>)>,
+    mut protected_query: ProtectedQuery,
+    mut spawn_requests: EventWriter<SpawnEvent<GameObject, SpawnData>>,
+    sources: LevelSources,
+    registry: Res<GameObjectRegistry>,
+    asset_index: Res<AssetIndex>,
+    character_handles: Res<CharacterAssets>,
+    current_nav_mesh_settings: Res<NavMeshSettings>,
 ) -> Result<()> {
-    for load in load_requests.iter() {
-        let path = Path::new("levels")
-            .join(load.filename.clone())
-            .with_extension("lvl.ron")
-            .to_str()
-            .with_context(|| {
-                format!(
-                    "Failed to convert path to string for filename: {}",
-                    load.filename
-                )
-            })?
-            .to_string();
-        let handle = match level_handles.levels.get(&path) {
-            Some(handle) => handle,
-            None => {
-                error!(
-                    "Failed to load scene \"{}\": No such level. Available levels: {:?}",
-                    path,
-                    level_handles.levels.keys()
-                );
+    for LevelAssetsLoaded(load) in load_requests.iter() {
+        let (level, expanded) = match sources
+            .get(&load.filename)
+            .and_then(|level| Ok((level, sources.expand(level)?)))
+        {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                error!("Failed to load scene \"{}\": {e:#}", load.filename);
+                finished_events.send(WorldLoadFinished {
+                    request: load.clone(),
+                    success: false,
+                });
                 continue;
             }
         };
-        let spawn_events = &levels
-            .get(handle)
-            .context("Failed to get level from handle in level assets")?;
-        let spawn_events = Vec::<SpawnEvent<GameObject, Transform>>::from(*spawn_events);
+        let nav_mesh_settings = nav_mesh_settings(level.streaming.as_ref());
+        let validator = LevelValidator {
+            registry: &registry,
+            dialog_exists: &|dialog| {
+                // Dialogs are only loaded together with the levels that use them
+                asset_index.contains(&get_dialog_asset_path(dialog))
+            },
+            character_exists: &|character| {
+                character_handles
+                    .characters
+                    .contains_key(&get_character_asset_path(character))
+            },
+            get_level: &|name| {
+                sources
+                    .get(name)
+                    .and_then(|level| sources.expand(level))
+                    .ok()
+            },
+            world_half_extents: nav_mesh_settings.world_half_extents,
+            world_bottom_bound: nav_mesh_settings.world_bottom_bound,
+        };
+        let problems = validator.validate(&expanded);
+        if !problems.is_empty() {
+            error!(
+                "Level \"{}\" has {} problems:{}",
+                load.filename,
+                problems.len(),
+                format_level_problems(&problems)
+            );
+        }
+
+        // Protected objects that also belong to this level are already there
+        let protected_ids: HashSet<_> = protected_query.iter().map(|(_, id, ..)| *id).collect();
+        let mut streaming = level.streaming.clone().map(LevelStreaming::new);
+        let mut spawn_events = Vec::new();
+        for entry in expanded.objects.iter() {
+            if protected_ids.contains(&entry.id) {
+                continue;
+            }
+            if let Some(streaming) = &mut streaming && registry.is_streamed(&entry.object) {
+                // Spawned once the player gets close to it
+                streaming.store(entry.clone().into());
+                continue;
+            }
+            spawn_events.extend(entry.spawn_event(&registry));
+        }
         for entity in &current_spawn_query {
             commands
                 .get_entity(entity)
@@ -137,54 +383,211 @@ fn load_world(
         for event in spawn_events.into_iter() {
             spawn_requests.send(event);
         }
+        if let Some(spawn_point) = &load.spawn_point {
+            let spawn_transform = expanded
+                .spawn_points(&registry)
+                .find(|(name, _)| name == spawn_point)
+                .map(|(_, entry)| entry.transform)
+                .unwrap_or_else(|| {
+                    error!(
+                        "Level \"{}\" has no spawn point named \"{spawn_point}\"",
+                        load.filename
+                    );
+                    FALLBACK_SPAWN_TRANSFORM
+                });
+            place_arrivals(
+                &mut commands,
+                &mut protected_query,
+                &mut spawn_requests,
+                spawn_transform,
+            );
+        }
+
         commands.insert_resource(CurrentLevel {
             scene: load.filename.clone(),
         });
+        commands.insert_resource(LoadedLevel {
+            level: level.clone(),
+            expanded,
+        });
+        match streaming {
+            Some(streaming) => commands.insert_resource(streaming),
+            None => commands.remove_resource::<LevelStreaming>(),
+        }
+        if nav_mesh_settings.world_half_extents != current_nav_mesh_settings.world_half_extents {
+            // Tiles are laid out relative to the extents, so the ones built for the previous level are useless
+            commands.insert_resource(nav_mesh_settings);
+            commands.insert_resource(NavMesh::default());
+        }
         commands.insert_resource(InteractionOpportunities::default());
         commands.insert_resource(ActiveConditions::default());
         commands.remove_resource::<CurrentDialog>();
 
+        finished_events.send(WorldLoadFinished {
+            request: load.clone(),
+            success: true,
+        });
         info!("Successfully loaded scene \"{}\"", load.filename,)
     }
     Ok(())
 }
 
-fn serialize_world(spawn_query: &Query<(&GameObject, Option<&Transform>)>) -> Result<String> {
-    let objects: Vec<_> = spawn_query
-        .iter()
-        .filter(|(game_object, _)| **game_object != GameObject::Player)
-        .map(|(game_object, transform)| {
-            SpawnEvent::with_data(
-                *game_object,
-                transform.map(Clone::clone).unwrap_or_default(),
+/// Moves the protected player and followers to the spawn point, or spawns the player there if
+/// they did not come along from another level.
+fn place_arrivals(
+    commands: &mut Commands,
+    protected_query: &mut ProtectedQuery,
+    spawn_requests: &mut EventWriter<SpawnEvent<GameObject, SpawnData>>,
+    spawn_transform: Transform,
+) {
+    let mut player_arrived = false;
+    let mut follower_count = 0;
+    for (entity, _id, mut transform, velocity, player) in protected_query.iter_mut() {
+        *transform = if player.is_some() {
+            player_arrived = true;
+            spawn_transform
+        } else {
+            follower_count += 1;
+            spawn_transform.with_translation(
+                spawn_transform.translation
+                    + spawn_transform.back() * FOLLOWER_SPACING * follower_count as f32,
             )
-        })
-        .collect();
-    let serialized_level = SerializedLevel::from(objects);
-    ron::ser::to_string_pretty(&serialized_level, default()).context("Failed to serialize world")
+        };
+        if let Some(mut velocity) = velocity {
+            *velocity = Velocity::zero();
+        }
+        commands.entity(entity).remove::<Protected>();
+    }
+    if !player_arrived {
+        // Make sure the player is spawned after the level
+        spawn_requests.send(
+            SpawnEvent::with_data(GameObject::PLAYER, SpawnData::from(spawn_transform))
+                .delay_frames(2),
+        );
+    }
+}
+
+/// Returns the name of a level in [`LevelStorage`], e.g. `old_town.lvl.ron` for `old_town`.
+pub fn get_level_file_name(filename: &str) -> String {
+    format!("{filename}.lvl.ron")
+}
+
+/// Returns the key of a level in [`LevelAssets::levels`], e.g. `levels/old_town.lvl.ron` for `old_town`.
+/// Asset paths always use `/`, like the entries of the [`AssetIndex`].
+pub fn get_level_asset_path(filename: &str) -> String {
+    format!("levels/{}", get_level_file_name(filename))
+}
+
+type LevelObjectQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static GameObject,
+        Option<&'static Transform>,
+        &'static PersistentId,
+    ),
+>;
+
+/// Collects the objects of the world that belong in a level file.
+fn snapshot_level<'a>(
+    world: &World,
+    objects: impl Iterator<
+        Item = (
+            Entity,
+            &'a GameObject,
+            Option<&'a Transform>,
+            &'a PersistentId,
+        ),
+    >,
+    registry: &GameObjectRegistry,
+    loaded_level: Option<&LoadedLevel>,
+) -> SerializedLevel {
+    // Objects belonging to prefab instances are written as references to their prefab instead.
+    let is_instanced = |id: &PersistentId| {
+        loaded_level.map_or(false, |loaded| loaded.expanded.instanced.contains(id))
+    };
+    let objects = objects
+        .filter(|(_, _, _, id)| !is_instanced(id))
+        .filter_map(|(entity, game_object, transform, id)| {
+            let registration = registry.get(game_object)?;
+            registration.saved_in_levels.then(|| LevelObject {
+                id: *id,
+                object: game_object.clone(),
+                transform: transform.map(Clone::clone).unwrap_or_default(),
+                properties: (registration.read_properties)(world.entity(entity)),
+            })
+        });
+    // Objects in unloaded cells are not spawned, but still part of the level
+    let unloaded_objects = world
+        .get_resource::<LevelStreaming>()
+        .into_iter()
+        .flat_map(LevelStreaming::unloaded_objects)
+        .map(|unloaded| &unloaded.object)
+        .filter(|object| !is_instanced(&object.id))
+        .filter(|object| registry.is_saved_in_levels(&object.object))
+        .cloned();
+    let objects = objects.chain(unloaded_objects).collect();
+    let prefabs = loaded_level
+        .map(|loaded| loaded.level.prefabs.clone())
+        .unwrap_or_default();
+    let streaming = loaded_level.and_then(|loaded| loaded.level.streaming.clone());
+    let assets = loaded_level
+        .map(|loaded| loaded.level.assets.clone())
+        .unwrap_or_default();
+    SerializedLevel {
+        objects,
+        prefabs,
+        streaming,
+        assets,
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Reflect, Serialize, Deserialize, TypeUuid, Deref, DerefMut)]
+#[derive(Debug, Clone, PartialEq, Reflect, Serialize, Deserialize, TypeUuid, Default)]
 #[uuid = "eb7cc7bc-5a97-41ed-b0c3-0d4e2137b73b"]
 #[reflect(Serialize, Deserialize)]
-pub struct SerializedLevel(pub Vec<(GameObject, Transform)>);
-
-impl From<Vec<SpawnEvent<GameObject, Transform>>> for SerializedLevel {
-    fn from(events: Vec<SpawnEvent<GameObject, Transform>>) -> Self {
-        Self(
-            events
-                .into_iter()
-                .map(|event| (event.object, event.data))
-                .collect(),
-        )
-    }
+pub struct SerializedLevel {
+    #[serde(default)]
+    pub objects: Vec<LevelObject>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prefabs: Vec<PrefabInstance>,
+    /// Streams the level in cells around the player instead of spawning it all at once.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub streaming: Option<StreamingSettings>,
+    /// Assets that are loaded together with the level.
+    #[serde(default, skip_serializing_if = "LevelAssetList::is_empty")]
+    pub assets: LevelAssetList,
+}
+
+/// A single object placed in a [`SerializedLevel`].
+#[derive(Debug, Clone, PartialEq, Reflect, FromReflect, Serialize, Deserialize)]
+#[reflect(Serialize, Deserialize)]
+pub struct LevelObject {
+    pub id: PersistentId,
+    pub object: GameObject,
+    pub transform: Transform,
+    #[serde(default, skip_serializing_if = "ObjectProperties::is_empty")]
+    pub properties: ObjectProperties,
 }
 
-impl From<&SerializedLevel> for Vec<SpawnEvent<GameObject, Transform>> {
-    fn from(level: &SerializedLevel) -> Self {
-        level
-            .iter()
-            .map(|(object, transform)| SpawnEvent::with_data(*object, *transform))
-            .collect()
+impl LevelObject {
+    pub fn spawn_data(&self) -> SpawnData {
+        SpawnData::new(self.transform, self.id).with_properties(self.properties.clone())
+    }
+
+    /// Creates a spawn request for the object. Objects that are not registered in the
+    /// [`GameObjectRegistry`] are skipped with an error message.
+    pub fn spawn_event(
+        &self,
+        registry: &GameObjectRegistry,
+    ) -> Option<SpawnEvent<GameObject, SpawnData>> {
+        let event = registry.spawn_event(&self.object, self.spawn_data());
+        if event.is_none() {
+            error!(
+                "Failed to spawn level object {:?}: Unknown game object \"{}\"",
+                self.id, self.object
+            );
+        }
+        event
     }
 }
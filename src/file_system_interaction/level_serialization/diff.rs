@@ -0,0 +1,120 @@
+use crate::file_system_interaction::level_serialization::{LevelObject, SerializedLevel};
+use crate::util::trait_extension::TransformExt;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use std::fmt;
+
+/// How a level differs from another version of it, e.g. the one on disk. Objects are matched by their ID.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LevelDiff {
+    pub added: Vec<LevelObject>,
+    pub removed: Vec<LevelObject>,
+    /// Objects with a different transform, as `(old, new)`.
+    pub moved: Vec<(LevelObject, LevelObject)>,
+    /// Objects with different properties, as `(old, new)`. Objects can be both moved and changed.
+    pub changed: Vec<(LevelObject, LevelObject)>,
+    /// Whether prefab instances were added, removed or edited.
+    pub prefabs_changed: bool,
+}
+
+impl LevelDiff {
+    pub fn between(old: &SerializedLevel, new: &SerializedLevel) -> Self {
+        let old_objects: HashMap<_, _> = old
+            .objects
+            .iter()
+            .map(|object| (object.id, object))
+            .collect();
+        let new_objects: HashMap<_, _> = new
+            .objects
+            .iter()
+            .map(|object| (object.id, object))
+            .collect();
+        // An ID that now belongs to another kind of object counts as a removal and an addition
+        let is_same_object = |object: &LevelObject, other: Option<&&LevelObject>| {
+            other.map_or(false, |other| other.object == object.object)
+        };
+
+        let mut diff = Self {
+            prefabs_changed: old.prefabs != new.prefabs,
+            ..default()
+        };
+        for object in old.objects.iter() {
+            if !is_same_object(object, new_objects.get(&object.id)) {
+                diff.removed.push(object.clone());
+            }
+        }
+        for object in new.objects.iter() {
+            let old_object = old_objects.get(&object.id);
+            if !is_same_object(object, old_object) {
+                diff.added.push(object.clone());
+                continue;
+            }
+            let Some(old_object) = old_object else {
+                continue;
+            };
+            if !old_object.transform.is_approx_eq(object.transform) {
+                diff.moved.push(((*old_object).clone(), object.clone()));
+            }
+            if old_object.properties != object.properties {
+                diff.changed.push(((*old_object).clone(), object.clone()));
+            }
+        }
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.moved.is_empty()
+            && self.changed.is_empty()
+            && !self.prefabs_changed
+    }
+
+    /// One line per difference, e.g. `+ Orb 42 at [0, 1, 0]`.
+    pub fn describe(&self) -> Vec<String> {
+        let name = |object: &LevelObject| format!("{} {}", object.object, object.id.0);
+        self.added
+            .iter()
+            .map(|object| format!("+ {} at {}", name(object), object.transform.translation))
+            .chain(
+                self.removed
+                    .iter()
+                    .map(|object| format!("- {}", name(object))),
+            )
+            .chain(self.moved.iter().map(|(old, new)| {
+                format!(
+                    "~ {} moved from {} to {}",
+                    name(new),
+                    old.transform.translation,
+                    new.transform.translation
+                )
+            }))
+            .chain(
+                self.changed
+                    .iter()
+                    .map(|(_, new)| format!("* {} has new properties", name(new))),
+            )
+            .chain(
+                self.prefabs_changed
+                    .then(|| "* Prefab instances changed".to_owned()),
+            )
+            .collect()
+    }
+}
+
+impl fmt::Display for LevelDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} added, {} removed, {} moved, {} changed",
+            self.added.len(),
+            self.removed.len(),
+            self.moved.len(),
+            self.changed.len()
+        )?;
+        if self.prefabs_changed {
+            write!(f, ", prefab instances changed")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+use crate::file_system_interaction::storage::Storage;
+use anyhow::{Context, Result};
+use chrono::prelude::Utc;
+
+/// Not a level extension, so old versions are never mistaken for levels.
+const HISTORY_EXTENSION: &str = "lvl.ron.bak";
+/// How many old versions of each level are kept.
+const HISTORY_LENGTH: usize = 20;
+
+/// Copies the level stored as `file_name` into the `history` storage before it is overwritten, dropping the oldest
+/// versions that don't fit anymore. Returns the name of the copy.
+pub(super) fn back_up_level(
+    levels: &dyn Storage,
+    history: &dyn Storage,
+    level: &str,
+    file_name: &str,
+) -> Result<String> {
+    let data = levels
+        .read(file_name)
+        .with_context(|| format!("Failed to read {file_name} for backing it up"))?;
+    let timestamp = Utc::now().format("%Y-%m-%dT%H-%M-%S%.3f");
+    let backup_name = format!("{level}/{timestamp}.{HISTORY_EXTENSION}");
+    history
+        .write(&backup_name, &data)
+        .with_context(|| format!("Failed to write backup {backup_name}"))?;
+
+    let mut versions = level_history(history, level)?;
+    // Timestamps sort chronologically, so the oldest versions come first
+    versions.sort();
+    let excess = versions.len().saturating_sub(HISTORY_LENGTH);
+    for old_version in versions.iter().take(excess) {
+        history.delete(old_version)?;
+    }
+    Ok(backup_name)
+}
+
+/// Names of all old versions of a level in the history storage, in no particular order.
+pub fn level_history(storage: &dyn Storage, level: &str) -> Result<Vec<String>> {
+    let prefix = format!("{level}/");
+    let suffix = format!(".{HISTORY_EXTENSION}");
+    Ok(storage
+        .list()?
+        .into_iter()
+        .filter(|name| name.starts_with(&prefix) && name.ends_with(&suffix))
+        .collect())
+}
@@ -0,0 +1,197 @@
+use crate::file_system_interaction::level_serialization::{
+    get_level_asset_path, CurrentLevel, ExpandedLevel, LevelDiff, LevelObject, LevelSources,
+    LoadedLevel, Prefab, SerializedLevel,
+};
+use crate::level_instantiation::spawning::{GameObject, GameObjectRegistry, PersistentId};
+use crate::level_instantiation::streaming::LevelStreaming;
+use crate::util::trait_extension::TransformExt;
+use anyhow::Result;
+use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
+use bevy_mod_sysfail::macros::*;
+
+/// The current level after its file or one of its prefabs changed on disk.
+#[derive(Debug, Clone, Resource)]
+pub(super) struct PendingLevelReload {
+    level: SerializedLevel,
+    expanded: ExpandedLevel,
+}
+
+#[sysfail(log(level = "error"))]
+pub(super) fn queue_level_reload(
+    mut commands: Commands,
+    mut level_events: EventReader<AssetEvent<SerializedLevel>>,
+    mut prefab_events: EventReader<AssetEvent<Prefab>>,
+    current_level: Option<Res<CurrentLevel>>,
+    sources: LevelSources,
+) -> Result<()> {
+    let modified_levels: HashSet<_> = level_events
+        .iter()
+        .filter_map(|event| match event {
+            AssetEvent::Modified { handle } => Some(handle.clone()),
+            _ => None,
+        })
+        .collect();
+    // Any prefab might be instanced by the current level, so they all trigger a reload
+    let prefab_modified = prefab_events
+        .iter()
+        .any(|event| matches!(event, AssetEvent::Modified { .. }));
+    let Some(current_level) = current_level else {
+        return Ok(());
+    };
+    let filename = &current_level.scene;
+    let level_modified = sources
+        .level_handles
+        .levels
+        .get(&get_level_asset_path(filename))
+        .map_or(false, |handle| modified_levels.contains(handle));
+    if !level_modified && !prefab_modified {
+        return Ok(());
+    }
+
+    let level = sources.get(filename)?.clone();
+    match sources.expand(&level) {
+        Ok(expanded) => {
+            info!("Level \"{filename}\" changed on disk, reloading it");
+            commands.insert_resource(PendingLevelReload { level, expanded });
+        }
+        Err(e) => error!("Failed to reload level \"{filename}\": {e:#}"),
+    }
+    Ok(())
+}
+
+/// Respawns only the objects that differ between the previously loaded version of the level and the new one.
+/// Everything else, e.g. the player, the camera, conditions and the current dialog, stays as it is.
+pub(super) fn apply_level_reload(world: &mut World) {
+    let Some(PendingLevelReload { level, expanded }) =
+        world.remove_resource::<PendingLevelReload>()
+    else {
+        return;
+    };
+    let Some(loaded) = world.get_resource::<LoadedLevel>() else {
+        return;
+    };
+    if loaded.level.streaming != level.streaming {
+        warn!("The streaming settings of the level changed. Load the level again to apply them");
+    }
+    if loaded.level.assets != level.assets {
+        warn!("The asset list of the level changed. Load the level again to load the new assets");
+    }
+    let as_level = |expanded: &ExpandedLevel| SerializedLevel {
+        objects: expanded.objects.clone(),
+        ..default()
+    };
+    let diff = LevelDiff::between(&as_level(&loaded.expanded), &as_level(&expanded));
+    if diff.is_empty() {
+        world.insert_resource(LoadedLevel { level, expanded });
+        return;
+    }
+    info!("Applying level changes: {diff}");
+
+    let mut query = world.query::<(Entity, &PersistentId, &GameObject, Option<&Transform>)>();
+    let live_objects: HashMap<_, _> = query
+        .iter(world)
+        .map(|(entity, id, object, transform)| (*id, (entity, object.clone(), transform.copied())))
+        .collect();
+    let registry = world.resource::<GameObjectRegistry>();
+    // Objects that already look like the new version, e.g. because the file was just saved from the
+    // running game, are left alone
+    let is_up_to_date = |new: &LevelObject| {
+        live_objects
+            .get(&new.id)
+            .map_or(false, |(entity, object, transform)| {
+                let properties = registry
+                    .get(object)
+                    .map(|registration| (registration.read_properties)(world.entity(*entity)));
+                *object == new.object
+                    && transform.map_or(false, |transform| transform.is_approx_eq(new.transform))
+                    && properties.as_ref() == Some(&new.properties)
+            })
+    };
+
+    // Objects that don't belong in level files, e.g. the player, are never touched
+    let is_unmanaged = |id: &PersistentId| {
+        live_objects.get(id).map_or(false, |(_, object, _)| {
+            registry.contains(object) && !registry.is_saved_in_levels(object)
+        })
+    };
+
+    let changed_ids: HashSet<_> = diff.changed.iter().map(|(_, new)| new.id).collect();
+    let mut removals = Vec::new();
+    let mut moves = Vec::new();
+    let mut additions = Vec::new();
+    for object in diff.removed.iter() {
+        if !is_unmanaged(&object.id) {
+            removals.push(object.id);
+        }
+    }
+    for (_, new) in diff.moved.iter() {
+        // Changed objects are respawned anyway
+        if !changed_ids.contains(&new.id) && !is_unmanaged(&new.id) && !is_up_to_date(new) {
+            moves.push(new.clone());
+        }
+    }
+    for new in diff
+        .added
+        .iter()
+        .chain(diff.changed.iter().map(|(_, new)| new))
+    {
+        if !is_unmanaged(&new.id) && !is_up_to_date(new) {
+            removals.push(new.id);
+            additions.push(new.clone());
+        }
+    }
+    let streamed: HashSet<_> = additions
+        .iter()
+        .chain(moves.iter())
+        .filter(|object| registry.is_streamed(&object.object))
+        .map(|object| object.id)
+        .collect();
+    let (live_removals, stored_removals): (Vec<_>, Vec<_>) = removals
+        .into_iter()
+        .partition(|id| live_objects.contains_key(id));
+    let (live_moves, stored_moves): (Vec<_>, Vec<_>) = moves
+        .into_iter()
+        .partition(|object| live_objects.contains_key(&object.id));
+
+    let mut streaming = world.remove_resource::<LevelStreaming>();
+    for id in live_removals {
+        let (entity, ..) = live_objects[&id];
+        world.entity_mut(entity).despawn_recursive();
+    }
+    for new in live_moves {
+        let (entity, ..) = live_objects[&new.id];
+        if let Some(mut transform) = world.get_mut::<Transform>(entity) {
+            *transform = new.transform;
+        }
+    }
+    if let Some(streaming) = &mut streaming {
+        for id in stored_removals {
+            streaming.take(id);
+        }
+        // Stored objects are simply stored again at their new position
+        for new in stored_moves {
+            if streaming.take(new.id).is_some() {
+                additions.push(new);
+            }
+        }
+    }
+    let registry = world.resource::<GameObjectRegistry>();
+    let mut spawn_events = Vec::new();
+    for new in additions {
+        let new = match &mut streaming {
+            Some(streaming) if streamed.contains(&new.id) => {
+                streaming.store(new.into()).map(|unloaded| unloaded.object)
+            }
+            _ => Some(new),
+        };
+        spawn_events.extend(new.and_then(|new| new.spawn_event(registry)));
+    }
+    for event in spawn_events {
+        world.send_event(event);
+    }
+    if let Some(streaming) = streaming {
+        world.insert_resource(streaming);
+    }
+    world.insert_resource(LoadedLevel { level, expanded });
+}
@@ -0,0 +1,272 @@
+use crate::file_system_interaction::asset_loading::{
+    AssetKind, DialogAssets, LevelAssets, NamedAssets, PrefabAssets,
+};
+use crate::file_system_interaction::level_serialization::{
+    expand_level, get_level_asset_path, Prefab, SerializedLevel, WorldLoadRequest,
+};
+use crate::level_instantiation::spawning::{
+    GameObject, GameObjectRegistry, LevelScene, TransitionTarget,
+};
+use crate::world_interaction::dialog::{get_dialog_asset_path, DialogId};
+use anyhow::Result;
+use bevy::asset::{HandleId, LoadState};
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use bevy_mod_sysfail::macros::*;
+use serde::{Deserialize, Serialize};
+
+/// Assets a level needs besides the ones every level uses, by their names in the asset manifest.
+/// They are loaded before the level is spawned and unloaded once a level that doesn't need them is loaded.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Reflect, FromReflect, Serialize, Deserialize)]
+#[reflect(Serialize, Deserialize)]
+#[serde(default)]
+pub struct LevelAssetList {
+    /// Scenes imported by `Level` objects are added automatically.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub scenes: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub textures: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub audio: Vec<String>,
+    /// Dialogs of NPCs are added automatically.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub dialogs: Vec<DialogId>,
+}
+
+impl LevelAssetList {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// How many of the assets of the level that is being loaded are done. Only exists while a level loads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Resource)]
+pub struct LevelLoadingProgress {
+    pub done: u32,
+    pub total: u32,
+}
+
+impl LevelLoadingProgress {
+    pub fn fraction(&self) -> f32 {
+        self.done as f32 / self.total.max(1) as f32
+    }
+}
+
+/// A [`WorldLoadRequest`] whose assets are ready, so that the level can be spawned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct LevelAssetsLoaded(pub(super) WorldLoadRequest);
+
+#[derive(Debug, Clone, Resource)]
+pub(super) struct PendingLevelLoad {
+    request: WorldLoadRequest,
+    level: Handle<SerializedLevel>,
+    /// `None` until the level file itself is loaded.
+    dependencies: Option<LevelDependencies>,
+}
+
+/// Everything a level uses. Whatever is not in here is unloaded once the level is ready.
+#[derive(Debug, Clone, Default)]
+struct LevelDependencies {
+    named: HashSet<(AssetKind, String)>,
+    dialogs: HashSet<String>,
+    /// The level itself and the ones its transitions lead to, so that transitions can be validated.
+    levels: HashSet<String>,
+    /// Descriptions for error messages, e.g. `scene "old_town"`, and the handles to wait for.
+    handles: Vec<(String, HandleId)>,
+}
+
+#[sysfail(log(level = "error"))]
+pub(super) fn start_level_loading(
+    mut commands: Commands,
+    mut load_requests: EventReader<WorldLoadRequest>,
+    asset_server: Res<AssetServer>,
+    mut level_handles: ResMut<LevelAssets>,
+) -> Result<()> {
+    // A newer request replaces any older one
+    let Some(request) = load_requests.iter().last() else {
+        return Ok(());
+    };
+    let path = get_level_asset_path(&request.filename);
+    let level = level_handles
+        .levels
+        .entry(path.clone())
+        .or_insert_with(|| asset_server.load(path.as_str()))
+        .clone();
+    commands.insert_resource(PendingLevelLoad {
+        request: request.clone(),
+        level,
+        dependencies: None,
+    });
+    commands.insert_resource(LevelLoadingProgress { done: 0, total: 1 });
+    Ok(())
+}
+
+/// Loads the assets of the pending level and hands it over to `load_world` once they are done.
+pub(super) fn track_level_loading(
+    mut commands: Commands,
+    mut pending: ResMut<PendingLevelLoad>,
+    asset_server: Res<AssetServer>,
+    levels: Res<Assets<SerializedLevel>>,
+    prefabs: Res<Assets<Prefab>>,
+    prefab_handles: Res<PrefabAssets>,
+    registry: Res<GameObjectRegistry>,
+    mut level_handles: ResMut<LevelAssets>,
+    mut dialog_handles: ResMut<DialogAssets>,
+    mut named_assets: ResMut<NamedAssets>,
+    mut loaded_events: EventWriter<LevelAssetsLoaded>,
+) {
+    let is_done = |id: HandleId| {
+        matches!(
+            asset_server.get_load_state(id),
+            LoadState::Loaded | LoadState::Failed
+        )
+    };
+    if pending.dependencies.is_none() {
+        if !is_done(pending.level.id()) {
+            return;
+        }
+        // A level that failed to load is reported by `load_world`
+        let dependencies = levels.get(&pending.level).map(|level| {
+            let mut dependencies = LevelDependencies::of(
+                &pending.request.filename,
+                level,
+                &prefabs,
+                &prefab_handles,
+                &registry,
+            );
+            dependencies.request(
+                &asset_server,
+                &mut level_handles,
+                &mut dialog_handles,
+                &mut named_assets,
+            );
+            dependencies
+        });
+        pending.dependencies = Some(dependencies.unwrap_or_default());
+    }
+    let Some(dependencies) = &pending.dependencies else {
+        return;
+    };
+
+    let done = dependencies
+        .handles
+        .iter()
+        .filter(|(_, id)| is_done(*id))
+        .count();
+    // The level file itself counts as one asset
+    commands.insert_resource(LevelLoadingProgress {
+        done: done as u32 + 1,
+        total: dependencies.handles.len() as u32 + 1,
+    });
+    if done < dependencies.handles.len() {
+        return;
+    }
+
+    for (description, id) in dependencies.handles.iter() {
+        if asset_server.get_load_state(*id) == LoadState::Failed {
+            error!(
+                "Failed to load {description} for level \"{}\"",
+                pending.request.filename
+            );
+        }
+    }
+    if !dependencies.levels.is_empty() {
+        level_handles
+            .levels
+            .retain(|path, _| dependencies.levels.contains(path));
+        dialog_handles
+            .dialogs
+            .retain(|path, _| dependencies.dialogs.contains(path));
+        named_assets.retain(&dependencies.named);
+    }
+    loaded_events.send(LevelAssetsLoaded(pending.request.clone()));
+    commands.remove_resource::<PendingLevelLoad>();
+    commands.remove_resource::<LevelLoadingProgress>();
+}
+
+impl LevelDependencies {
+    fn of(
+        filename: &str,
+        level: &SerializedLevel,
+        prefabs: &Assets<Prefab>,
+        prefab_handles: &PrefabAssets,
+        registry: &GameObjectRegistry,
+    ) -> Self {
+        let mut dependencies = Self::default();
+        let assets = &level.assets;
+        let named = [
+            (AssetKind::Scene, &assets.scenes),
+            (AssetKind::Texture, &assets.textures),
+            (AssetKind::Audio, &assets.audio),
+        ];
+        for (kind, names) in named {
+            dependencies
+                .named
+                .extend(names.iter().map(|name| (kind, name.clone())));
+        }
+
+        // Broken prefabs are reported by `load_world`, so just look at what is there
+        let expanded = expand_level(level, prefabs, prefab_handles).ok();
+        let objects = expanded
+            .as_ref()
+            .map_or(&level.objects, |expanded| &expanded.objects);
+        let mut dialogs: HashSet<_> = assets.dialogs.iter().cloned().collect();
+        let mut levels = vec![filename.to_owned()];
+        for object in objects.iter() {
+            let properties = match registry.get(&object.object) {
+                Some(registration) => object
+                    .properties
+                    .clone()
+                    .with_defaults(&registration.default_properties),
+                None => object.properties.clone(),
+            };
+            dialogs.extend(properties.get::<DialogId>().cloned());
+            if let Some(LevelScene(scene)) = properties.get::<LevelScene>() {
+                dependencies.named.insert((AssetKind::Scene, scene.clone()));
+            }
+            if object.object == GameObject::TRANSITION
+                && let Some(target) = properties.get::<TransitionTarget>()
+            {
+                levels.push(target.level.clone());
+            }
+        }
+        dependencies.dialogs = dialogs.iter().map(get_dialog_asset_path).collect();
+        dependencies.levels = levels
+            .iter()
+            .map(|level| get_level_asset_path(level))
+            .collect();
+        dependencies
+    }
+
+    /// Starts loading everything that is not loaded yet.
+    fn request(
+        &mut self,
+        asset_server: &AssetServer,
+        level_handles: &mut LevelAssets,
+        dialog_handles: &mut DialogAssets,
+        named_assets: &mut NamedAssets,
+    ) {
+        for (kind, name) in self.named.iter() {
+            match named_assets.request(*kind, name, asset_server) {
+                Some(id) => self.handles.push((format!("{kind} \"{name}\""), id)),
+                None => {
+                    error!("The level needs {kind} \"{name}\", which is not in the asset manifest")
+                }
+            }
+        }
+        for path in self.dialogs.iter() {
+            let handle = dialog_handles
+                .dialogs
+                .entry(path.clone())
+                .or_insert_with(|| asset_server.load(path.as_str()));
+            self.handles.push((format!("dialog {path}"), handle.id()));
+        }
+        for path in self.levels.iter() {
+            let handle = level_handles
+                .levels
+                .entry(path.clone())
+                .or_insert_with(|| asset_server.load(path.as_str()));
+            self.handles.push((format!("level {path}"), handle.id()));
+        }
+    }
+}
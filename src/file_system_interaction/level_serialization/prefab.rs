@@ -0,0 +1,162 @@
+use crate::file_system_interaction::level_serialization::{LevelObject, SerializedLevel};
+use crate::level_instantiation::spawning::{
+    GameObject, GameObjectRegistry, ObjectProperties, PersistentId, SpawnData, SpawnPointName,
+};
+use anyhow::{bail, Context, Result};
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy::utils::HashSet;
+use serde::{Deserialize, Serialize};
+use spew::prelude::*;
+
+/// A reusable group of objects, e.g. a shrine made of an orb, two lights and an NPC.
+/// Stored like a level in `assets/prefabs/<name>.pfb.ron` and placed in levels through a [`PrefabInstance`].
+/// Prefabs can contain instances of other prefabs.
+#[derive(Debug, Clone, PartialEq, Reflect, Serialize, Deserialize, TypeUuid, Deref, DerefMut)]
+#[uuid = "5d3c9a7e-2f1b-4e8a-9c6d-0b7a4f2e8d13"]
+#[reflect(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Prefab(pub SerializedLevel);
+
+/// A prefab placed in a level or in another prefab.
+#[derive(Debug, Clone, PartialEq, Reflect, FromReflect, Serialize, Deserialize)]
+#[reflect(Serialize, Deserialize)]
+pub struct PrefabInstance {
+    /// The IDs of the objects spawned for this instance are derived from this ID, see [`PersistentId::nested`].
+    pub id: PersistentId,
+    /// The name of the prefab, e.g. `shrine` for `prefabs/shrine.pfb.ron`.
+    pub prefab: String,
+    /// Applied on top of the transforms of the objects in the prefab.
+    pub transform: Transform,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub overrides: Vec<PropertyOverride>,
+}
+
+/// Replaces properties of a single object of a prefab for one instance only.
+#[derive(Debug, Clone, PartialEq, Reflect, FromReflect, Serialize, Deserialize)]
+#[reflect(Serialize, Deserialize)]
+pub struct PropertyOverride {
+    /// The ID of the object as written in the prefab file. Only objects placed directly in the prefab can be
+    /// overridden, not the ones of prefabs nested in it.
+    pub id: PersistentId,
+    /// Properties that are set here replace the ones from the prefab, the others are kept.
+    pub properties: ObjectProperties,
+}
+
+/// A level with all of its prefab instances replaced by the objects they consist of.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ExpandedLevel {
+    pub objects: Vec<LevelObject>,
+    /// IDs of the objects in [`ExpandedLevel::objects`] that were spawned as part of a prefab instance.
+    pub instanced: HashSet<PersistentId>,
+}
+
+impl ExpandedLevel {
+    /// Expands all prefab instances of `level` recursively. `get_prefab` looks up prefabs by name.
+    /// Fails if a prefab is missing or contains an instance of itself.
+    pub fn expand<'a>(
+        level: &SerializedLevel,
+        get_prefab: impl Fn(&str) -> Option<&'a SerializedLevel>,
+    ) -> Result<Self> {
+        let mut expanded = Self {
+            objects: level.objects.clone(),
+            instanced: default(),
+        };
+        let mut stack = Vec::new();
+        for instance in level.prefabs.iter() {
+            expanded.expand_instance(instance, &get_prefab, &mut stack)?;
+        }
+        Ok(expanded)
+    }
+
+    fn expand_instance<'a>(
+        &mut self,
+        instance: &PrefabInstance,
+        get_prefab: &impl Fn(&str) -> Option<&'a SerializedLevel>,
+        stack: &mut Vec<String>,
+    ) -> Result<()> {
+        if let Some(start) = stack.iter().position(|name| *name == instance.prefab) {
+            let mut cycle = stack[start..].to_vec();
+            cycle.push(instance.prefab.clone());
+            bail!("Prefab contains itself: {}", cycle.join(" -> "));
+        }
+        let prefab = get_prefab(&instance.prefab)
+            .with_context(|| format!("No such prefab: \"{}\"", instance.prefab))?;
+
+        stack.push(instance.prefab.clone());
+        for object in prefab.objects.iter() {
+            let properties = match instance
+                .overrides
+                .iter()
+                .find(|property_override| property_override.id == object.id)
+            {
+                Some(property_override) => property_override
+                    .properties
+                    .clone()
+                    .with_defaults(&object.properties),
+                None => object.properties.clone(),
+            };
+            let id = instance.id.nested(object.id);
+            self.instanced.insert(id);
+            self.objects.push(LevelObject {
+                id,
+                object: object.object.clone(),
+                transform: instance.transform.mul_transform(object.transform),
+                properties,
+            });
+        }
+        for nested in prefab.prefabs.iter() {
+            let nested = PrefabInstance {
+                id: instance.id.nested(nested.id),
+                prefab: nested.prefab.clone(),
+                transform: instance.transform.mul_transform(nested.transform),
+                overrides: nested.overrides.clone(),
+            };
+            self.expand_instance(&nested, get_prefab, stack)
+                .with_context(|| format!("Failed to expand prefab \"{}\"", instance.prefab))?;
+        }
+        stack.pop();
+        Ok(())
+    }
+
+    /// All spawn points of the level along with their names. Spawn points without a name get the default one.
+    pub fn spawn_points<'a>(
+        &'a self,
+        registry: &GameObjectRegistry,
+    ) -> impl Iterator<Item = (String, &'a LevelObject)> {
+        let defaults = registry
+            .get(&GameObject::SPAWN_POINT)
+            .map(|registration| registration.default_properties.clone())
+            .unwrap_or_default();
+        self.objects
+            .iter()
+            .filter(|entry| entry.object == GameObject::SPAWN_POINT)
+            .filter_map(move |entry| {
+                let properties = entry.properties.clone().with_defaults(&defaults);
+                let name = properties.get::<SpawnPointName>()?;
+                Some((name.0.clone(), entry))
+            })
+    }
+
+    /// Creates a spawn request for every object. Objects that are not registered in the
+    /// [`GameObjectRegistry`] are skipped with an error message.
+    pub fn spawn_events(
+        &self,
+        registry: &GameObjectRegistry,
+    ) -> Vec<SpawnEvent<GameObject, SpawnData>> {
+        self.objects
+            .iter()
+            .filter_map(|entry| entry.spawn_event(registry))
+            .collect()
+    }
+}
+
+/// Returns the name of a prefab file, e.g. `shrine.pfb.ron` for `shrine`.
+pub fn get_prefab_file_name(name: &str) -> String {
+    format!("{name}.pfb.ron")
+}
+
+/// Returns the key of a prefab in `PrefabAssets::prefabs`, e.g. `prefabs/shrine.pfb.ron` for `shrine`.
+pub fn get_prefab_asset_path(name: &str) -> String {
+    format!("prefabs/{}", get_prefab_file_name(name))
+}
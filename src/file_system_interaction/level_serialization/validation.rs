@@ -0,0 +1,225 @@
+use crate::file_system_interaction::level_serialization::ExpandedLevel;
+use crate::level_instantiation::spawning::{
+    CharacterId, GameObject, GameObjectRegistry, PersistentId, TransitionTarget,
+};
+use crate::world_interaction::dialog::DialogId;
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use std::fmt;
+
+/// Checks levels for mistakes that would otherwise only show up while playing them,
+/// e.g. an NPC whose dialog does not exist.
+pub struct LevelValidator<'a> {
+    pub registry: &'a GameObjectRegistry,
+    pub dialog_exists: &'a dyn Fn(&DialogId) -> bool,
+    pub character_exists: &'a dyn Fn(&str) -> bool,
+    /// Returns the level with the given name. Used to check the targets of transitions.
+    pub get_level: &'a dyn Fn(&str) -> Option<ExpandedLevel>,
+    /// Objects must stay inside the navmesh, see
+    /// [`nav_mesh_settings`](crate::movement::navigation::nav_mesh_settings).
+    pub world_half_extents: f32,
+    pub world_bottom_bound: f32,
+}
+
+/// A mistake found by a [`LevelValidator`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum LevelProblem {
+    DuplicateId(PersistentId),
+    UnregisteredObject {
+        id: PersistentId,
+        object: GameObject,
+    },
+    /// A property the object's registration does not declare, so its spawner ignores it.
+    UnusedProperty {
+        id: PersistentId,
+        object: GameObject,
+        property: &'static str,
+    },
+    MissingDialog {
+        id: PersistentId,
+        dialog: DialogId,
+    },
+    MissingCharacter {
+        id: PersistentId,
+        character: String,
+    },
+    OutOfBounds {
+        id: PersistentId,
+        object: GameObject,
+        translation: Vec3,
+    },
+    DuplicateSpawnPoint(String),
+    MissingTransitionTarget(PersistentId),
+    MissingTargetLevel {
+        id: PersistentId,
+        level: String,
+    },
+    MissingTargetSpawnPoint {
+        id: PersistentId,
+        level: String,
+        spawn_point: String,
+    },
+}
+
+impl LevelValidator<'_> {
+    /// Returns every problem of the level, or nothing if it is valid.
+    pub fn validate(&self, level: &ExpandedLevel) -> Vec<LevelProblem> {
+        let mut problems = Vec::new();
+        let mut ids = HashSet::new();
+        for entry in level.objects.iter() {
+            let id = entry.id;
+            if !ids.insert(id) {
+                problems.push(LevelProblem::DuplicateId(id));
+            }
+            let Some(registration) = self.registry.get(&entry.object) else {
+                problems.push(LevelProblem::UnregisteredObject {
+                    id,
+                    object: entry.object.clone(),
+                });
+                continue;
+            };
+            problems.extend(
+                entry
+                    .properties
+                    .keys()
+                    .filter(|property| !registration.uses_property(property))
+                    .map(|property| LevelProblem::UnusedProperty {
+                        id,
+                        object: entry.object.clone(),
+                        property,
+                    }),
+            );
+            let properties = entry
+                .properties
+                .clone()
+                .with_defaults(&registration.default_properties);
+
+            if let Some(dialog) = properties.get::<DialogId>() && !(self.dialog_exists)(dialog) {
+                problems.push(LevelProblem::MissingDialog {
+                    id,
+                    dialog: dialog.clone(),
+                });
+            }
+            if let Some(CharacterId(character)) = properties.get::<CharacterId>()
+                && !(self.character_exists)(character)
+            {
+                problems.push(LevelProblem::MissingCharacter {
+                    id,
+                    character: character.clone(),
+                });
+            }
+            let translation = entry.transform.translation;
+            if translation.x.abs() > self.world_half_extents
+                || translation.z.abs() > self.world_half_extents
+                || translation.y < self.world_bottom_bound
+            {
+                problems.push(LevelProblem::OutOfBounds {
+                    id,
+                    object: entry.object.clone(),
+                    translation,
+                });
+            }
+            if entry.object == GameObject::TRANSITION {
+                match properties.get::<TransitionTarget>() {
+                    Some(target) => problems.extend(self.validate_transition_target(
+                        id,
+                        &target.level,
+                        &target.spawn_point,
+                    )),
+                    None => problems.push(LevelProblem::MissingTransitionTarget(id)),
+                }
+            }
+        }
+
+        let mut spawn_points = HashSet::new();
+        for (name, _) in level.spawn_points(self.registry) {
+            if !spawn_points.insert(name.clone()) {
+                problems.push(LevelProblem::DuplicateSpawnPoint(name));
+            }
+        }
+        problems
+    }
+
+    fn validate_transition_target(
+        &self,
+        id: PersistentId,
+        level: &str,
+        spawn_point: &str,
+    ) -> Option<LevelProblem> {
+        let Some(target_level) = (self.get_level)(level) else {
+            return Some(LevelProblem::MissingTargetLevel {
+                id,
+                level: level.to_owned(),
+            });
+        };
+        let has_spawn_point = target_level
+            .spawn_points(self.registry)
+            .any(|(name, _)| name == spawn_point);
+        (!has_spawn_point).then(|| LevelProblem::MissingTargetSpawnPoint {
+            id,
+            level: level.to_owned(),
+            spawn_point: spawn_point.to_owned(),
+        })
+    }
+}
+
+impl fmt::Display for LevelProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateId(id) => write!(f, "ID {} is used more than once", id.0),
+            Self::UnregisteredObject { id, object } => {
+                write!(f, "Object {} is of unknown type \"{object}\"", id.0)
+            }
+            Self::UnusedProperty {
+                id,
+                object,
+                property,
+            } => write!(
+                f,
+                "{object} {} has property \"{property}\", which it does not use",
+                id.0
+            ),
+            Self::MissingDialog { id, dialog } => {
+                write!(f, "Object {} uses missing dialog \"{}\"", id.0, dialog.0)
+            }
+            Self::MissingCharacter { id, character } => {
+                write!(f, "Object {} uses missing character \"{character}\"", id.0)
+            }
+            Self::OutOfBounds {
+                id,
+                object,
+                translation,
+            } => write!(
+                f,
+                "{object} {} at {translation} is outside of the navmesh bounds",
+                id.0
+            ),
+            Self::DuplicateSpawnPoint(name) => {
+                write!(f, "Spawn point \"{name}\" exists more than once")
+            }
+            Self::MissingTransitionTarget(id) => {
+                write!(f, "Transition {} has no target", id.0)
+            }
+            Self::MissingTargetLevel { id, level } => {
+                write!(f, "Transition {} leads to missing level \"{level}\"", id.0)
+            }
+            Self::MissingTargetSpawnPoint {
+                id,
+                level,
+                spawn_point,
+            } => write!(
+                f,
+                "Transition {} leads to spawn point \"{spawn_point}\", which level \"{level}\" does not have",
+                id.0
+            ),
+        }
+    }
+}
+
+/// Joins problems into a single message with one problem per line.
+pub fn format_level_problems(problems: &[LevelProblem]) -> String {
+    problems
+        .iter()
+        .map(|problem| format!("\n- {problem}"))
+        .collect()
+}
@@ -0,0 +1,268 @@
+use crate::file_system_interaction::game_state_serialization::SaveStorage;
+use crate::file_system_interaction::level_serialization::{LevelHistoryStorage, LevelStorage};
+use anyhow::{Context, Result};
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use glob::glob;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+
+pub fn storage_plugin(app: &mut App) {
+    app.register_type::<StorageBackend>()
+        .init_resource::<StorageBackend>()
+        .add_system(apply_storage_backend.run_if(
+            resource_changed::<StorageBackend>().and_then(not(resource_added::<StorageBackend>())),
+        ));
+}
+
+/// Selects which [`Storage`] implementation backs [`SaveStorage`], [`LevelStorage`] and [`LevelHistoryStorage`].
+/// Changing this resource at runtime swaps out all of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Resource, Reflect, FromReflect)]
+#[reflect(Resource)]
+pub enum StorageBackend {
+    /// `saves/`, `assets/levels/` and `level_history/` relative to the working directory.
+    FileSystem,
+    /// `saves/`, `levels/` and `level_history/` inside the platform's user data directory,
+    /// e.g. `~/.local/share/the-motion-in-everything` on Linux.
+    UserDataDir,
+    /// Nothing is persisted. Used on the web, where there is no file system.
+    Memory,
+}
+
+impl Default for StorageBackend {
+    fn default() -> Self {
+        if cfg!(feature = "wasm") {
+            Self::Memory
+        } else {
+            Self::FileSystem
+        }
+    }
+}
+
+/// What a [`Storage`] created by a [`StorageBackend`] is used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageLocation {
+    Saves,
+    Levels,
+    /// Old versions of overwritten levels. Kept outside of `assets/` so that they are not indexed as levels.
+    LevelHistory,
+}
+
+impl StorageBackend {
+    pub fn open(self, location: StorageLocation) -> Result<Arc<dyn Storage>> {
+        Ok(match self {
+            Self::FileSystem => Arc::new(FileStorage::new(match location {
+                StorageLocation::Saves => "saves",
+                StorageLocation::Levels => "assets/levels",
+                StorageLocation::LevelHistory => "level_history",
+            })),
+            Self::UserDataDir => Arc::new(FileStorage::in_user_data_dir(match location {
+                StorageLocation::Saves => "saves",
+                StorageLocation::Levels => "levels",
+                StorageLocation::LevelHistory => "level_history",
+            })?),
+            Self::Memory => Arc::new(MemoryStorage::default()),
+        })
+    }
+
+    /// Like [`StorageBackend::open`], but falls back to [`MemoryStorage`] if the storage is not available.
+    pub fn open_or_memory(self, location: StorageLocation) -> Arc<dyn Storage> {
+        self.open(location).unwrap_or_else(|e| {
+            error!("Failed to open {location:?} storage, nothing will be persisted: {e:#}");
+            Arc::new(MemoryStorage::default())
+        })
+    }
+}
+
+fn apply_storage_backend(
+    backend: Res<StorageBackend>,
+    mut save_storage: ResMut<SaveStorage>,
+    mut level_storage: ResMut<LevelStorage>,
+    mut level_history_storage: ResMut<LevelHistoryStorage>,
+) {
+    save_storage.set_storage(backend.open_or_memory(StorageLocation::Saves));
+    *level_storage = LevelStorage(backend.open_or_memory(StorageLocation::Levels));
+    *level_history_storage =
+        LevelHistoryStorage(backend.open_or_memory(StorageLocation::LevelHistory));
+    info!("Switched storage backend to {:?}", *backend);
+}
+
+/// Reads and writes named blobs of data, e.g. save files.
+/// Names are relative paths using `/` as separator, e.g. `backups/autosave.1.sav.ron`.
+pub trait Storage: Send + Sync + 'static {
+    /// Names of all blobs in this storage.
+    fn list(&self) -> Result<Vec<String>>;
+
+    fn read(&self, name: &str) -> Result<Vec<u8>>;
+
+    /// Replaces the blob with `data`. Must be atomic: if the game crashes while writing, the blob keeps its old
+    /// contents instead of ending up partially written.
+    fn write(&self, name: &str, data: &[u8]) -> Result<()>;
+
+    fn delete(&self, name: &str) -> Result<()>;
+
+    fn exists(&self, name: &str) -> bool {
+        self.list()
+            .map(|names| names.iter().any(|existing| existing == name))
+            .unwrap_or_default()
+    }
+
+    /// When the blob was last written, if the storage keeps track of it.
+    fn modified(&self, _name: &str) -> Option<SystemTime> {
+        None
+    }
+}
+
+/// Stores blobs as files in a directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileStorage {
+    root: PathBuf,
+}
+
+impl FileStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Stores blobs in `subdir` inside the platform's user data directory.
+    pub fn in_user_data_dir(subdir: &str) -> Result<Self> {
+        let data_dir = dirs::data_dir().context("Failed to find user data directory")?;
+        Ok(Self::new(data_dir.join(APP_DIRECTORY).join(subdir)))
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.root.join(name)
+    }
+}
+
+const TEMP_EXTENSION: &str = "tmp";
+const APP_DIRECTORY: &str = "the-motion-in-everything";
+
+impl Storage for FileStorage {
+    fn list(&self) -> Result<Vec<String>> {
+        let pattern = self.root.join("**").join("*");
+        let pattern = pattern
+            .to_str()
+            .with_context(|| format!("Failed to convert path to string: {pattern:?}"))?;
+        let names = glob(pattern)
+            .context("Failed to read glob pattern")?
+            .filter_map(|entry| entry.ok())
+            .filter(|path| path.is_file())
+            .filter(|path| path.extension().map_or(true, |ext| ext != TEMP_EXTENSION))
+            .filter_map(|path| {
+                let relative = path.strip_prefix(&self.root).ok()?;
+                let components: Option<Vec<_>> = relative
+                    .components()
+                    .map(|component| component.as_os_str().to_str())
+                    .collect();
+                Some(components?.join("/"))
+            })
+            .collect();
+        Ok(names)
+    }
+
+    fn read(&self, name: &str) -> Result<Vec<u8>> {
+        let path = self.path(name);
+        fs::read(&path).with_context(|| format!("Failed to read {}", path.to_string_lossy()))
+    }
+
+    fn write(&self, name: &str, data: &[u8]) -> Result<()> {
+        let path = self.path(name);
+        let dir = path.parent().context("Failed to get parent directory")?;
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create directory {}", dir.to_string_lossy()))?;
+
+        // Write everything to a temporary file first and only then move it over the old file,
+        // so that a crash can never leave a half-written file behind.
+        let mut temp_path = path.clone().into_os_string();
+        temp_path.push(".");
+        temp_path.push(TEMP_EXTENSION);
+        let temp_path = PathBuf::from(temp_path);
+        let mut file = File::create(&temp_path)
+            .with_context(|| format!("Failed to create {}", temp_path.to_string_lossy()))?;
+        file.write_all(data)
+            .with_context(|| format!("Failed to write {}", temp_path.to_string_lossy()))?;
+        file.sync_all()
+            .with_context(|| format!("Failed to flush {}", temp_path.to_string_lossy()))?;
+        drop(file);
+        fs::rename(&temp_path, &path).with_context(|| {
+            format!(
+                "Failed to move {} to {}",
+                temp_path.to_string_lossy(),
+                path.to_string_lossy()
+            )
+        })?;
+        sync_dir(dir);
+        Ok(())
+    }
+
+    fn delete(&self, name: &str) -> Result<()> {
+        let path = self.path(name);
+        fs::remove_file(&path)
+            .with_context(|| format!("Failed to delete {}", path.to_string_lossy()))
+    }
+
+    fn exists(&self, name: &str) -> bool {
+        self.path(name).is_file()
+    }
+
+    fn modified(&self, name: &str) -> Option<SystemTime> {
+        fs::metadata(self.path(name))
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+}
+
+/// Makes sure a rename inside `dir` survives a power loss.
+/// Not all platforms support opening directories, so this is best effort.
+fn sync_dir(dir: &Path) {
+    if let Ok(dir) = File::open(dir) {
+        let _ = dir.sync_all();
+    }
+}
+
+/// Keeps blobs in memory. Useful for tests and platforms without a file system.
+/// Clones share the same blobs.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStorage {
+    blobs: Arc<RwLock<HashMap<String, Vec<u8>>>>,
+}
+
+impl Storage for MemoryStorage {
+    fn list(&self) -> Result<Vec<String>> {
+        Ok(self.blobs.read().unwrap().keys().cloned().collect())
+    }
+
+    fn read(&self, name: &str) -> Result<Vec<u8>> {
+        self.blobs
+            .read()
+            .unwrap()
+            .get(name)
+            .cloned()
+            .with_context(|| format!("No such blob: {name}"))
+    }
+
+    fn write(&self, name: &str, data: &[u8]) -> Result<()> {
+        self.blobs
+            .write()
+            .unwrap()
+            .insert(name.to_owned(), data.to_vec());
+        Ok(())
+    }
+
+    fn delete(&self, name: &str) -> Result<()> {
+        self.blobs
+            .write()
+            .unwrap()
+            .remove(name)
+            .map(|_| ())
+            .with_context(|| format!("No such blob: {name}"))
+    }
+
+    fn exists(&self, name: &str) -> bool {
+        self.blobs.read().unwrap().contains_key(name)
+    }
+}
@@ -0,0 +1,138 @@
+use crate::file_system_interaction::config::GameConfig;
+use crate::file_system_interaction::storage::{FileStorage, MemoryStorage, Storage};
+use anyhow::{Context, Result};
+use bevy::prelude::*;
+use bevy_mod_sysfail::macros::*;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Name of the settings file in [`SettingsStorage`].
+const SETTINGS_FILE: &str = "settings.ron";
+/// Seconds to wait after the last change before writing the settings, so that dragging a slider
+/// doesn't write the file every frame.
+const SAVE_DELAY: f32 = 0.5;
+
+pub fn user_settings_plugin(app: &mut App) {
+    app.init_resource::<SettingsStorage>()
+        .add_startup_system(load_user_settings)
+        .add_system(save_user_settings.run_if(resource_exists::<UserSettings>()));
+}
+
+/// The player's own choices, layered on top of the [`GameConfig`] shipped with the game.
+/// `None` keeps the shipped value. Changes are applied immediately and written to the user data directory.
+#[derive(Debug, Clone, PartialEq, Resource, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct UserSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mouse_sensitivity_x: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mouse_sensitivity_y: Option<f32>,
+    /// In radians.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_fov: Option<f32>,
+    /// In radians.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_fov: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub letters_per_second: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volume: Option<f32>,
+}
+
+impl UserSettings {
+    /// Overrides the fields of `config` that the player has changed.
+    pub fn apply(&self, config: &mut GameConfig) {
+        let overrides = [
+            (
+                self.mouse_sensitivity_x,
+                &mut config.camera.mouse_sensitivity_x,
+            ),
+            (
+                self.mouse_sensitivity_y,
+                &mut config.camera.mouse_sensitivity_y,
+            ),
+            (self.min_fov, &mut config.player.min_fov),
+            (self.max_fov, &mut config.player.max_fov),
+            (
+                self.letters_per_second,
+                &mut config.dialog.base_letters_per_second,
+            ),
+            (self.volume, &mut config.audio.volume),
+        ];
+        for (setting, value) in overrides {
+            if let Some(setting) = setting {
+                *value = setting;
+            }
+        }
+    }
+}
+
+/// Where [`UserSettings`] are stored. Unlike saves, settings always live in the user data directory,
+/// or in memory on the web.
+#[derive(Clone, Resource)]
+pub struct SettingsStorage(pub Arc<dyn Storage>);
+
+impl Default for SettingsStorage {
+    fn default() -> Self {
+        if cfg!(feature = "wasm") {
+            return Self(Arc::new(MemoryStorage::default()));
+        }
+        match FileStorage::in_user_data_dir("settings") {
+            Ok(storage) => Self(Arc::new(storage)),
+            Err(e) => {
+                error!("Failed to open settings storage, settings will not be persisted: {e:#}");
+                Self(Arc::new(MemoryStorage::default()))
+            }
+        }
+    }
+}
+
+impl SettingsStorage {
+    pub fn read(&self) -> Result<Option<UserSettings>> {
+        if !self.0.exists(SETTINGS_FILE) {
+            return Ok(None);
+        }
+        let serialized = self.0.read(SETTINGS_FILE)?;
+        let serialized =
+            std::str::from_utf8(&serialized).context("Settings are not valid UTF-8")?;
+        let settings = ron::from_str(serialized).context("Failed to parse settings")?;
+        Ok(Some(settings))
+    }
+
+    pub fn write(&self, settings: &UserSettings) -> Result<()> {
+        let serialized = ron::ser::to_string_pretty(settings, default())
+            .context("Failed to serialize settings")?;
+        self.0.write(SETTINGS_FILE, serialized.as_bytes())
+    }
+}
+
+fn load_user_settings(mut commands: Commands, storage: Res<SettingsStorage>) {
+    let settings = storage.read().unwrap_or_else(|e| {
+        error!("Failed to read settings, using the defaults instead: {e:#}");
+        None
+    });
+    commands.insert_resource(settings.unwrap_or_default());
+}
+
+#[sysfail(log(level = "error"))]
+fn save_user_settings(
+    time: Res<Time>,
+    settings: Res<UserSettings>,
+    storage: Res<SettingsStorage>,
+    mut save_timer: Local<Option<Timer>>,
+) -> Result<()> {
+    if settings.is_changed() && !settings.is_added() {
+        *save_timer = Some(Timer::from_seconds(SAVE_DELAY, TimerMode::Once));
+    }
+    let Some(timer) = save_timer.as_mut() else {
+        return Ok(());
+    };
+    // The pause menu pauses the regular time
+    if !timer.tick(time.raw_delta()).finished() {
+        return Ok(());
+    }
+    *save_timer = None;
+    storage.write(&settings)?;
+    info!("Saved settings");
+    Ok(())
+}
@@ -1,10 +1,18 @@
+use crate::file_system_interaction::config::GameConfig;
+use crate::file_system_interaction::game_state_serialization::{
+    GameDeleteRequest, GameLoadRequest, GameSaveRequest, SaveSlot, SaveSlots,
+};
+use crate::file_system_interaction::user_settings::UserSettings;
+use crate::menu::show_options;
 use crate::player_control::actions::{ActionsFrozen, UiAction};
 use crate::GameState;
 use bevy::prelude::*;
 use bevy_egui::{egui, EguiContexts};
 use leafwing_input_manager::prelude::ActionState;
+use std::time::Duration;
 
 /// Handles the pause menu accessed while playing the game via ESC.
+/// The pause menu also contains the save browser and the options.
 pub fn ingame_menu_plugin(app: &mut App) {
     app.add_system(handle_pause.in_set(OnUpdate(GameState::Playing)));
 }
@@ -15,15 +23,20 @@ fn handle_pause(
     mut actions_frozen: ResMut<ActionsFrozen>,
     mut egui_contexts: EguiContexts,
     mut paused: Local<bool>,
+    mut showing_options: Local<bool>,
+    mut save_label: Local<String>,
+    save_slots: Res<SaveSlots>,
+    mut save_requests: EventWriter<GameSaveRequest>,
+    mut load_requests: EventWriter<GameLoadRequest>,
+    mut delete_requests: EventWriter<GameDeleteRequest>,
+    mut settings: ResMut<UserSettings>,
+    config: Res<GameConfig>,
 ) {
     for action in actions.iter() {
         let toggled = action.just_pressed(UiAction::TogglePause);
         if *paused {
-            if toggled {
-                *paused = false;
-                time.unpause();
-                actions_frozen.unfreeze();
-            } else {
+            let mut save_browser_action = None;
+            if !toggled {
                 egui::CentralPanel::default()
                     .frame(egui::Frame {
                         fill: egui::Color32::from_black_alpha(240),
@@ -37,9 +50,50 @@ fn handle_pause(
                             ui.heading("Game Paused");
                             ui.separator();
                             ui.label("Press ESC to resume");
+                            ui.add_space(30.0);
+                            if *showing_options {
+                                ui.heading("Options");
+                                if let Some(edited) = show_options(ui, &settings, &config) {
+                                    *settings = edited;
+                                }
+                                ui.add_space(10.0);
+                                if ui.button("Back to saves").clicked() {
+                                    *showing_options = false;
+                                }
+                            } else {
+                                if ui.button("Options").clicked() {
+                                    *showing_options = true;
+                                }
+                                ui.add_space(10.0);
+                                save_browser_action =
+                                    show_save_browser(ui, &save_slots, &mut save_label);
+                            }
                         });
                     });
             }
+            let resume = match save_browser_action {
+                Some(SaveBrowserAction::Save { slot }) => {
+                    let label = (!save_label.is_empty()).then(|| save_label.clone());
+                    save_requests.send(GameSaveRequest { slot, label });
+                    save_label.clear();
+                    false
+                }
+                Some(SaveBrowserAction::Load { slot }) => {
+                    load_requests.send(GameLoadRequest { slot: Some(slot) });
+                    true
+                }
+                Some(SaveBrowserAction::Delete { slot }) => {
+                    delete_requests.send(GameDeleteRequest { slot });
+                    false
+                }
+                None => toggled,
+            };
+            if resume {
+                *paused = false;
+                *showing_options = false;
+                time.unpause();
+                actions_frozen.unfreeze();
+            }
         } else if toggled {
             *paused = true;
             time.pause();
@@ -47,3 +101,88 @@ fn handle_pause(
         }
     }
 }
+
+enum SaveBrowserAction {
+    /// `None` creates a new slot
+    Save {
+        slot: Option<String>,
+    },
+    Load {
+        slot: String,
+    },
+    Delete {
+        slot: String,
+    },
+}
+
+fn show_save_browser(
+    ui: &mut egui::Ui,
+    save_slots: &SaveSlots,
+    save_label: &mut String,
+) -> Option<SaveBrowserAction> {
+    let mut action = None;
+    ui.heading("Saves");
+    ui.horizontal(|ui| {
+        ui.label("Label: ");
+        ui.text_edit_singleline(save_label);
+        if ui.button("Save as new").clicked() {
+            action = Some(SaveBrowserAction::Save { slot: None });
+        }
+    });
+    ui.separator();
+    egui::ScrollArea::vertical()
+        .auto_shrink([false; 2])
+        .show(ui, |ui| {
+            if save_slots.is_empty() {
+                ui.label("No saves yet");
+            }
+            for slot in save_slots.iter() {
+                ui.group(|ui| {
+                    ui.vertical(|ui| {
+                        ui.strong(slot.display_label());
+                        ui.label(describe_save_slot(slot));
+                        if let Some(objective) = &slot.metadata.objective {
+                            ui.label(format!("Objective: {objective}"));
+                        }
+                        ui.horizontal(|ui| {
+                            if ui.button("Load").clicked() {
+                                action = Some(SaveBrowserAction::Load {
+                                    slot: slot.id.clone(),
+                                });
+                            }
+                            if ui.button("Overwrite").clicked() {
+                                action = Some(SaveBrowserAction::Save {
+                                    slot: Some(slot.id.clone()),
+                                });
+                            }
+                            if ui.button("Delete").clicked() {
+                                action = Some(SaveBrowserAction::Delete {
+                                    slot: slot.id.clone(),
+                                });
+                            }
+                        });
+                    });
+                });
+            }
+        });
+    action
+}
+
+fn describe_save_slot(slot: &SaveSlot) -> String {
+    let timestamp = slot
+        .timestamp
+        .with_timezone(&chrono::Local)
+        .format("%Y-%m-%d %H:%M");
+    let playtime = format_playtime(slot.metadata.playtime);
+    format!("{} · {timestamp} · played {playtime}", slot.level)
+}
+
+fn format_playtime(playtime: Duration) -> String {
+    let seconds = playtime.as_secs();
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        (seconds / 60) % 60,
+        seconds % 60
+    )
+}
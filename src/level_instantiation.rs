@@ -1,10 +1,14 @@
 pub mod grass;
+pub mod level_transition;
 pub mod map;
 pub mod spawning;
+pub mod streaming;
 
 use crate::level_instantiation::grass::grass_plugin;
+use crate::level_instantiation::level_transition::level_transition_plugin;
 use crate::level_instantiation::map::map_plugin;
 use crate::level_instantiation::spawning::spawning_plugin;
+use crate::level_instantiation::streaming::streaming_plugin;
 use bevy::prelude::*;
 use seldom_fn_plugin::FnPluginExt;
 
@@ -12,8 +16,12 @@ use seldom_fn_plugin::FnPluginExt;
 /// - [`map_plugin`] handles loading of level files and orchestrates the spawning of the objects therein.
 /// - [`spawning_plugin`] handles the spawning of objects in general.
 /// - [`grass_plugin`] handles the spawning of grass on top of marked meshes.
+/// - [`level_transition_plugin`] moves the player between levels through transition volumes.
+/// - [`streaming_plugin`] loads and unloads cells of large levels around the player.
 pub fn level_instantiation_plugin(app: &mut App) {
     app.fn_plugin(map_plugin)
         .fn_plugin(spawning_plugin)
-        .fn_plugin(grass_plugin);
+        .fn_plugin(grass_plugin)
+        .fn_plugin(level_transition_plugin)
+        .fn_plugin(streaming_plugin);
 }
@@ -1,8 +1,10 @@
+use crate::level_instantiation::streaming::{LevelStreaming, StreamedGrass};
 use crate::util::trait_extension::MeshExt;
 use anyhow::{bail, Context, Result};
 use bevy::prelude::*;
 use bevy::render::mesh::{PrimitiveTopology, VertexAttributeValues};
 use bevy::transform::TransformSystem;
+use bevy::utils::HashMap;
 use bevy_mod_sysfail::macros::*;
 use rand::{rngs::SmallRng, Rng, SeedableRng};
 use warbler_grass::prelude::*;
@@ -23,6 +25,7 @@ pub fn add_grass(
     children_query: Query<&Children>,
     mesh_handles: Query<&Handle<Mesh>>,
     global_transforms: Query<&GlobalTransform>,
+    streaming: Option<Res<LevelStreaming>>,
 ) -> Result<()> {
     for (parent_entity, name) in added_name.iter() {
         if name.contains("[grass]") {
@@ -50,13 +53,34 @@ pub fn add_grass(
                     })
                     .collect();
                 let height = 0.7;
-                commands.spawn((
-                    Name::new("Grass"),
-                    WarblersExplicitBundle {
-                        grass: Grass { positions, height },
-                        ..default()
-                    },
-                ));
+                let Some(streaming) = &streaming else {
+                    commands.spawn((
+                        Name::new("Grass"),
+                        WarblersExplicitBundle {
+                            grass: Grass { positions, height },
+                            ..default()
+                        },
+                    ));
+                    continue;
+                };
+                let mut cells: HashMap<_, Vec<_>> = HashMap::new();
+                for position in positions {
+                    cells
+                        .entry(streaming.cell_of(position))
+                        .or_default()
+                        .push(position);
+                }
+                for (cell, positions) in cells {
+                    let grass = Grass { positions, height };
+                    commands.spawn((
+                        Name::new("Grass"),
+                        StreamedGrass {
+                            cell,
+                            grass: grass.clone(),
+                        },
+                        WarblersExplicitBundle { grass, ..default() },
+                    ));
+                }
             }
         }
     }
@@ -0,0 +1,142 @@
+use crate::file_system_interaction::level_serialization::{
+    Protected, WorldLoadFinished, WorldLoadRequest,
+};
+use crate::level_instantiation::spawning::objects::transition::Transition;
+use crate::movement::navigation::Follower;
+use crate::player_control::player_embodiment::Player;
+use crate::GameState;
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use bevy_rapier3d::prelude::*;
+
+const FADE_SECONDS: f32 = 0.5;
+
+/// Takes the player and their followers to another level when the player enters a [`Transition`] volume.
+/// The screen fades to black while the level is swapped.
+pub fn level_transition_plugin(app: &mut App) {
+    app.add_systems(
+        (
+            start_level_transition.run_if(not(resource_exists::<LevelTransition>())),
+            advance_level_transition.run_if(resource_exists::<LevelTransition>()),
+            show_fade.run_if(resource_exists::<LevelTransition>()),
+        )
+            .chain()
+            .in_set(OnUpdate(GameState::Playing)),
+    );
+}
+
+/// A level transition in progress.
+#[derive(Debug, Clone, Resource)]
+struct LevelTransition {
+    request: WorldLoadRequest,
+    phase: FadePhase,
+    timer: Timer,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FadePhase {
+    Out,
+    Loading,
+    In,
+}
+
+impl LevelTransition {
+    /// How much the screen is covered, from 0 to 1.
+    fn opacity(&self) -> f32 {
+        match self.phase {
+            FadePhase::Out => self.timer.percent(),
+            FadePhase::Loading => 1.0,
+            FadePhase::In => self.timer.percent_left(),
+        }
+    }
+}
+
+fn start_level_transition(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    player_query: Query<(), With<Player>>,
+    transition_query: Query<&Transition>,
+) {
+    for event in collision_events.iter() {
+        let CollisionEvent::Started(entity_a, entity_b, _kind) = event else {
+            continue;
+        };
+        let transition = [(*entity_a, *entity_b), (*entity_b, *entity_a)]
+            .iter()
+            .filter(|(player, _)| player_query.contains(*player))
+            .find_map(|(_, transition)| transition_query.get(*transition).ok());
+        if let Some(Transition { target }) = transition {
+            info!(
+                "Transitioning to spawn point \"{}\" in level \"{}\"",
+                target.spawn_point, target.level
+            );
+            commands.insert_resource(LevelTransition {
+                request: WorldLoadRequest {
+                    filename: target.level.clone(),
+                    spawn_point: Some(target.spawn_point.clone()),
+                },
+                phase: FadePhase::Out,
+                timer: Timer::from_seconds(FADE_SECONDS, TimerMode::Once),
+            });
+            return;
+        }
+    }
+}
+
+fn advance_level_transition(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut transition: ResMut<LevelTransition>,
+    mut loader: EventWriter<WorldLoadRequest>,
+    mut finished_events: EventReader<WorldLoadFinished>,
+    travellers: Query<Entity, Or<(With<Player>, With<Follower>)>>,
+    protected_query: Query<Entity, With<Protected>>,
+) {
+    match transition.phase {
+        FadePhase::Out => {
+            if !transition.timer.tick(time.delta()).finished() {
+                return;
+            }
+            for entity in travellers.iter() {
+                commands.entity(entity).insert(Protected);
+            }
+            loader.send(transition.request.clone());
+            transition.phase = FadePhase::Loading;
+        }
+        FadePhase::Loading => {
+            // The assets of the level may take a while to load
+            if !finished_events
+                .iter()
+                .any(|finished| finished.request == transition.request)
+            {
+                return;
+            }
+            // Arriving at the spawn point removes the protection,
+            // so anything still protected means the level failed to load
+            for entity in protected_query.iter() {
+                commands.entity(entity).remove::<Protected>();
+            }
+            transition.phase = FadePhase::In;
+            transition.timer.reset();
+        }
+        FadePhase::In => {
+            if transition.timer.tick(time.delta()).finished() {
+                commands.remove_resource::<LevelTransition>();
+            }
+        }
+    }
+}
+
+fn show_fade(transition: Res<LevelTransition>, mut egui_contexts: EguiContexts) {
+    let ctx = egui_contexts.ctx_mut();
+    let alpha = (transition.opacity() * 255.).round() as u8;
+    ctx.layer_painter(egui::LayerId::new(
+        egui::Order::Foreground,
+        egui::Id::new("level-transition-fade"),
+    ))
+    .rect_filled(
+        ctx.screen_rect(),
+        0.,
+        egui::Color32::from_black_alpha(alpha),
+    );
+}
@@ -3,37 +3,46 @@ use crate::level_instantiation::spawning::despawn::{despawn, Despawn};
 use crate::level_instantiation::spawning::post_spawn_modification::{
     despawn_removed, set_color, set_hidden, set_shadows,
 };
+use crate::world_interaction::dialog::DialogId;
 use crate::GameState;
 pub use animation_link::AnimationEntityLink;
 use bevy::prelude::*;
+pub use character::{
+    get_character_asset_path, spawn_character, CharacterAnimationNames, CharacterDefinition,
+    CharacterId, CharacterModel, Characters,
+};
+pub use persistent_id::{PersistentId, PersistentIdLookup, PersistentIdLookupUpdate};
+pub use properties::{
+    GlowColor, LevelScene, LightProperties, ObjectProperties, ObjectProperty, PropertyType,
+    SpawnPointName, TransitionTarget,
+};
+pub use registry::{GameObjectRegistration, GameObjectRegistry, GameObjectRegistryExt};
+use seldom_fn_plugin::FnPluginExt;
 use serde::{Deserialize, Serialize};
 use spew::prelude::*;
-use strum_macros::EnumIter;
+use std::borrow::Cow;
+use std::fmt;
 
 mod animation_link;
+mod character;
 mod despawn;
 pub mod objects;
+mod persistent_id;
 mod post_spawn_modification;
+mod properties;
+mod registry;
 
 pub fn spawning_plugin(app: &mut App) {
-    app.add_plugin(SpewPlugin::<GameObject, Transform>::default())
+    app.add_plugin(SpewPlugin::<GameObject, SpawnData>::default())
+        .init_resource::<GameObjectRegistry>()
+        .fn_plugin(persistent_id::persistent_id_plugin)
         .register_type::<Despawn>()
         .register_type::<AnimationEntityLink>()
-        .add_spawners((
-            (GameObject::Empty, objects::primitives::spawn_empty),
-            (GameObject::Box, objects::primitives::spawn_box),
-            (GameObject::Triangle, objects::primitives::spawn_triangle),
-            (GameObject::Sphere, objects::primitives::spawn_sphere),
-            (GameObject::Capsule, objects::primitives::spawn_capsule),
-            (GameObject::Sunlight, objects::sunlight::spawn),
-            (GameObject::PointLight, objects::point_light::spawn),
-            (GameObject::Npc, objects::npc::spawn),
-            (GameObject::Player, objects::player::spawn),
-            (GameObject::Level, objects::level::spawn),
-            (GameObject::Orb, objects::orb::spawn),
-            (GameObject::Camera, objects::camera::spawn),
-            (GameObject::Skydome, objects::skydome::spawn),
-        ))
+        .register_type::<CharacterId>()
+        .register_type::<objects::checkpoint::Checkpoint>()
+        .register_type::<objects::spawn_point::SpawnPoint>()
+        .register_type::<objects::transition::Transition>()
+        .fn_plugin(register_builtin_objects)
         .add_systems((despawn, link_animations).in_set(OnUpdate(GameState::Playing)))
         .add_systems(
             (set_hidden, despawn_removed, set_color, set_shadows)
@@ -41,35 +50,186 @@ pub fn spawning_plugin(app: &mut App) {
         );
 }
 
+/// A registry of all built-in objects, for tools that inspect levels without running the game.
+pub fn builtin_object_registry() -> GameObjectRegistry {
+    let mut app = App::new();
+    register_builtin_objects(&mut app);
+    app.world
+        .remove_resource::<GameObjectRegistry>()
+        .unwrap_or_default()
+}
+
+fn register_builtin_objects(app: &mut App) {
+    use objects::{
+        camera, checkpoint, level, npc, orb, player, point_light, primitives, skydome, spawn_point,
+        sunlight, transition,
+    };
+    app.register_game_object(
+        GameObjectRegistration::new(GameObject::EMPTY),
+        primitives::spawn_empty,
+    )
+    .register_game_object(
+        GameObjectRegistration::new(GameObject::BOX),
+        primitives::spawn_box,
+    )
+    .register_game_object(
+        GameObjectRegistration::new(GameObject::TRIANGLE),
+        primitives::spawn_triangle,
+    )
+    .register_game_object(
+        GameObjectRegistration::new(GameObject::SPHERE),
+        primitives::spawn_sphere,
+    )
+    .register_game_object(
+        GameObjectRegistration::new(GameObject::CAPSULE),
+        primitives::spawn_capsule,
+    )
+    .register_game_object(
+        GameObjectRegistration::new(GameObject::SUNLIGHT).always_loaded(),
+        sunlight::spawn,
+    )
+    .register_game_object(
+        GameObjectRegistration::new(GameObject::POINT_LIGHT)
+            .with_default_property(LightProperties::default())
+            .with_property_reader(point_light::read_properties),
+        point_light::spawn,
+    )
+    .register_game_object(
+        GameObjectRegistration::new(GameObject::NPC)
+            .with_default_property(DialogId::new(npc::DEFAULT_DIALOG))
+            .with_default_property(CharacterId(npc::DEFAULT_CHARACTER.to_owned()))
+            .with_property_reader(npc::read_properties),
+        npc::spawn,
+    )
+    .register_game_object(
+        GameObjectRegistration::new(GameObject::PLAYER)
+            .unsaved()
+            .always_loaded(),
+        player::spawn,
+    )
+    .register_game_object(
+        // Streams its colliders and grass instead, see `LevelStreaming`
+        GameObjectRegistration::new(GameObject::LEVEL)
+            .always_loaded()
+            .with_default_property(LevelScene(level::DEFAULT_SCENE.to_owned()))
+            .with_property_reader(level::read_properties),
+        level::spawn,
+    )
+    .register_game_object(
+        GameObjectRegistration::new(GameObject::ORB)
+            .with_default_property(GlowColor(orb::DEFAULT_GLOW_COLOR))
+            .with_property_reader(orb::read_properties),
+        orb::spawn,
+    )
+    .register_game_object(
+        GameObjectRegistration {
+            // The camera's state is saved separately
+            saved_in_saves: false,
+            ..GameObjectRegistration::new(GameObject::CAMERA).always_loaded()
+        },
+        camera::spawn,
+    )
+    .register_game_object(
+        GameObjectRegistration::new(GameObject::SKYDOME).always_loaded(),
+        skydome::spawn,
+    )
+    .register_game_object(
+        GameObjectRegistration::new(GameObject::CHECKPOINT),
+        checkpoint::spawn,
+    )
+    .register_game_object(
+        GameObjectRegistration::new(GameObject::SPAWN_POINT)
+            .with_default_property(SpawnPointName(spawn_point::DEFAULT_SPAWN_POINT.to_owned()))
+            .with_property_reader(spawn_point::read_properties),
+        spawn_point::spawn,
+    )
+    .register_game_object(
+        GameObjectRegistration::new(GameObject::TRANSITION)
+            .with_property::<TransitionTarget>()
+            .with_property_reader(transition::read_properties),
+        transition::spawn,
+    );
+}
+
+/// Identifies a kind of spawnable object, e.g. `"Npc"`. Every kind is registered in the [`GameObjectRegistry`]
+/// together with the system that spawns it, so other plugins can add their own with
+/// [`GameObjectRegistryExt::register_game_object`].
 #[derive(
-    Debug,
-    EnumIter,
-    Component,
-    Clone,
-    Copy,
-    Eq,
-    PartialEq,
-    Hash,
-    Reflect,
-    FromReflect,
-    Serialize,
-    Deserialize,
-    Default,
+    Debug, Component, Clone, Eq, PartialEq, Hash, Reflect, FromReflect, Serialize, Deserialize,
 )]
 #[reflect(Component, Serialize, Deserialize)]
-pub enum GameObject {
-    #[default]
-    Empty,
-    Box,
-    Triangle,
-    Sphere,
-    Capsule,
-    Sunlight,
-    PointLight,
-    Npc,
-    Player,
-    Level,
-    Orb,
-    Camera,
-    Skydome,
+#[serde(transparent)]
+pub struct GameObject(pub Cow<'static, str>);
+
+impl GameObject {
+    pub const EMPTY: Self = Self::from_static("Empty");
+    pub const BOX: Self = Self::from_static("Box");
+    pub const TRIANGLE: Self = Self::from_static("Triangle");
+    pub const SPHERE: Self = Self::from_static("Sphere");
+    pub const CAPSULE: Self = Self::from_static("Capsule");
+    pub const SUNLIGHT: Self = Self::from_static("Sunlight");
+    pub const POINT_LIGHT: Self = Self::from_static("PointLight");
+    pub const NPC: Self = Self::from_static("Npc");
+    pub const PLAYER: Self = Self::from_static("Player");
+    pub const LEVEL: Self = Self::from_static("Level");
+    pub const ORB: Self = Self::from_static("Orb");
+    pub const CAMERA: Self = Self::from_static("Camera");
+    pub const SKYDOME: Self = Self::from_static("Skydome");
+    pub const CHECKPOINT: Self = Self::from_static("Checkpoint");
+    pub const SPAWN_POINT: Self = Self::from_static("SpawnPoint");
+    pub const TRANSITION: Self = Self::from_static("Transition");
+
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(Cow::Owned(id.into()))
+    }
+
+    pub const fn from_static(id: &'static str) -> Self {
+        Self(Cow::Borrowed(id))
+    }
+
+    pub fn id(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for GameObject {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+impl fmt::Display for GameObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Input passed to the spawner of a [`GameObject`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpawnData {
+    pub transform: Transform,
+    pub id: PersistentId,
+    pub properties: ObjectProperties,
+}
+
+impl SpawnData {
+    pub fn new(transform: Transform, id: PersistentId) -> Self {
+        Self {
+            transform,
+            id,
+            properties: default(),
+        }
+    }
+
+    pub fn with_properties(mut self, properties: ObjectProperties) -> Self {
+        self.properties = properties;
+        self
+    }
+}
+
+/// Spawns an object with a newly generated [`PersistentId`].
+impl From<Transform> for SpawnData {
+    fn from(transform: Transform) -> Self {
+        Self::new(transform, PersistentId::new())
+    }
 }
@@ -0,0 +1,151 @@
+use crate::file_system_interaction::asset_loading::{AssetKind, CharacterAssets, NamedAssets};
+use crate::movement::general_movement::{
+    CharacterAnimations, CharacterControllerBundle, Jumping, Model, Walking,
+};
+use anyhow::{Context, Result};
+use bevy::ecs::system::{EntityCommands, SystemParam};
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Describes how a character looks and moves, so that new creatures only need a data file.
+/// Read from `.char.ron` files in the `characters` directory, e.g. `assets/characters/player.char.ron`.
+#[derive(Debug, Clone, PartialEq, TypeUuid, Serialize, Deserialize)]
+#[uuid = "0d6f5c8e-3b1a-4f7d-9e2c-6a8b4c1d7e53"]
+pub struct CharacterDefinition {
+    pub model: CharacterModel,
+    /// Height of the capsule collider without its caps.
+    pub height: f32,
+    pub radius: f32,
+    /// Mass in kg.
+    pub mass: f32,
+    #[serde(default)]
+    pub walking: Walking,
+    #[serde(default)]
+    pub jumping: Jumping,
+    pub animations: CharacterAnimationNames,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CharacterModel {
+    /// Name of the scene in the asset manifest.
+    pub scene: String,
+    pub scale: f32,
+    /// Translation of the model relative to the center of the collider.
+    pub offset: Vec3,
+    /// Rotation around the up axis in degrees, for models that don't face forward.
+    #[serde(default)]
+    pub yaw: f32,
+}
+
+/// Names of the animations in the asset manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CharacterAnimationNames {
+    pub idle: String,
+    pub walk: String,
+    pub aerial: String,
+}
+
+impl CharacterDefinition {
+    /// Every asset of the manifest this character uses.
+    pub fn required_assets(&self) -> impl Iterator<Item = (AssetKind, &str)> {
+        [
+            (AssetKind::Scene, self.model.scene.as_str()),
+            (AssetKind::Animation, self.animations.idle.as_str()),
+            (AssetKind::Animation, self.animations.walk.as_str()),
+            (AssetKind::Animation, self.animations.aerial.as_str()),
+        ]
+        .into_iter()
+    }
+}
+
+/// Returns the key of a character in [`CharacterAssets::characters`], e.g. `characters/npc.char.ron` for `npc`.
+pub fn get_character_asset_path(name: &str) -> String {
+    format!("characters/{name}.char.ron")
+}
+
+/// Character definitions as loaded by the asset server.
+#[derive(SystemParam)]
+pub struct Characters<'w> {
+    handles: Res<'w, CharacterAssets>,
+    definitions: Res<'w, Assets<CharacterDefinition>>,
+}
+
+impl Characters<'_> {
+    pub fn get(&self, name: &str) -> Result<&CharacterDefinition> {
+        let path = get_character_asset_path(name);
+        let handle = self.handles.characters.get(&path).with_context(|| {
+            format!(
+                "No such character: {path}. Available characters: {:?}",
+                self.handles.characters.keys()
+            )
+        })?;
+        self.definitions
+            .get(handle)
+            .context("Failed to get character from handle in character assets")
+    }
+}
+
+/// The name of the [`CharacterDefinition`] an entity was spawned from.
+#[derive(Debug, Clone, PartialEq, Eq, Component, Reflect, Default, Serialize, Deserialize)]
+#[reflect(Component, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CharacterId(pub String);
+
+/// Spawns a character controller as described by the character definition, together with the model following it.
+/// Returns the controller so that spawners can add their own components.
+pub fn spawn_character<'w, 's, 'a>(
+    commands: &'a mut Commands<'w, 's>,
+    characters: &Characters,
+    assets: &NamedAssets,
+    character: &str,
+    transform: Transform,
+    name: &str,
+) -> Result<EntityCommands<'w, 's, 'a>> {
+    let definition = characters.get(character)?;
+    let entity = commands
+        .spawn((
+            PbrBundle {
+                transform,
+                ..default()
+            },
+            Name::new(name.to_owned()),
+            CharacterId(character.to_owned()),
+            CharacterControllerBundle {
+                mass: ColliderMassProperties::Mass(definition.mass),
+                walking: definition.walking.clone(),
+                jumping: definition.jumping.clone(),
+                ..CharacterControllerBundle::capsule(definition.height, definition.radius)
+            },
+            CharacterAnimations {
+                idle: assets.animation(&definition.animations.idle),
+                walk: assets.animation(&definition.animations.walk),
+                aerial: assets.animation(&definition.animations.aerial),
+            },
+        ))
+        .id();
+
+    let model = &definition.model;
+    commands
+        .spawn((
+            Model { target: entity },
+            SpatialBundle::default(),
+            Name::new(format!("{name} Model Parent")),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                SceneBundle {
+                    scene: assets.scene(&model.scene),
+                    transform: Transform {
+                        translation: model.offset,
+                        rotation: Quat::from_rotation_y(model.yaw.to_radians()),
+                        scale: Vec3::splat(model.scale),
+                    },
+                    ..default()
+                },
+                Name::new(format!("{name} Model")),
+            ));
+        });
+    Ok(commands.entity(entity))
+}
@@ -2,6 +2,7 @@ use bevy_rapier3d::prelude::*;
 use bitflags::bitflags;
 
 pub mod camera;
+pub mod checkpoint;
 pub mod level;
 pub mod npc;
 pub mod orb;
@@ -9,7 +10,9 @@ pub mod player;
 pub mod point_light;
 pub mod primitives;
 pub mod skydome;
+pub mod spawn_point;
 pub mod sunlight;
+pub mod transition;
 mod util;
 
 bitflags! {
@@ -1,4 +1,4 @@
-use crate::level_instantiation::spawning::GameObject;
+use crate::level_instantiation::spawning::{GameObject, SpawnData};
 use crate::player_control::actions::create_camera_action_input_manager_bundle;
 use crate::player_control::camera::IngameCamera;
 use bevy::prelude::*;
@@ -6,7 +6,7 @@ use bevy_dolly::prelude::*;
 #[cfg(feature = "dev")]
 use bevy_editor_pls::default_windows::cameras::EditorCamera;
 
-pub(crate) fn spawn(In(transform): In<Transform>, mut commands: Commands) {
+pub(crate) fn spawn(In(SpawnData { transform, id, .. }): In<SpawnData>, mut commands: Commands) {
     commands.spawn((
         IngameCamera::default(),
         Camera3dBundle {
@@ -22,7 +22,8 @@ pub(crate) fn spawn(In(transform): In<Transform>, mut commands: Commands) {
             .build(),
         create_camera_action_input_manager_bundle(),
         Name::new("Main Camera"),
-        GameObject::Camera,
+        GameObject::CAMERA,
+        id,
         #[cfg(feature = "dev")]
         EditorCamera,
     ));
@@ -0,0 +1,30 @@
+use crate::level_instantiation::spawning::objects::GameCollisionGroup;
+use crate::level_instantiation::spawning::{GameObject, SpawnData};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A trigger volume that autosaves the game when the player enters it.
+/// Covers a unit cube, so its size is set through the scale of its transform.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Component, Reflect, Serialize, Deserialize, Default,
+)]
+#[reflect(Component, Serialize, Deserialize)]
+pub struct Checkpoint;
+
+pub(crate) fn spawn(In(SpawnData { transform, id, .. }): In<SpawnData>, mut commands: Commands) {
+    commands.spawn((
+        TransformBundle::from_transform(transform),
+        Collider::cuboid(0.5, 0.5, 0.5),
+        Sensor,
+        ActiveEvents::COLLISION_EVENTS,
+        CollisionGroups::new(
+            GameCollisionGroup::OTHER.into(),
+            GameCollisionGroup::PLAYER.into(),
+        ),
+        Name::new("Checkpoint"),
+        Checkpoint,
+        GameObject::CHECKPOINT,
+        id,
+    ));
+}
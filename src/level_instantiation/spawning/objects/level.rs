@@ -1,21 +1,43 @@
-use crate::file_system_interaction::asset_loading::SceneAssets;
-use crate::level_instantiation::spawning::GameObject;
+use crate::file_system_interaction::asset_loading::NamedAssets;
+use crate::level_instantiation::spawning::{GameObject, LevelScene, ObjectProperties, SpawnData};
+use bevy::ecs::world::EntityRef;
 use bevy::prelude::*;
 
+/// The scene a level object imports when its properties don't name one.
+pub const DEFAULT_SCENE: &str = "old_town";
+
+pub(crate) fn read_properties(entity: EntityRef) -> ObjectProperties {
+    let mut properties = ObjectProperties::default();
+    if let Some(scene) = entity.get::<LevelScene>() {
+        properties.insert(scene.clone());
+    }
+    properties
+}
+
 pub(crate) fn spawn(
-    In(transform): In<Transform>,
+    In(SpawnData {
+        transform,
+        id,
+        properties,
+    }): In<SpawnData>,
     mut commands: Commands,
-    scene_handles: Res<SceneAssets>,
+    assets: Res<NamedAssets>,
 ) {
+    let scene = properties
+        .get::<LevelScene>()
+        .cloned()
+        .unwrap_or_else(|| LevelScene(DEFAULT_SCENE.to_owned()));
     commands.spawn((
         SceneBundle {
-            scene: scene_handles.level.clone(),
+            scene: assets.scene(&scene.0),
             transform,
             ..default()
         },
         Name::new("Level"),
         Imported,
-        GameObject::Level,
+        scene,
+        GameObject::LEVEL,
+        id,
     ));
 }
 
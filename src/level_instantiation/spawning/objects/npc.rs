@@ -1,74 +1,77 @@
-use crate::file_system_interaction::asset_loading::{AnimationAssets, SceneAssets};
+use crate::file_system_interaction::asset_loading::NamedAssets;
 use crate::level_instantiation::spawning::objects::GameCollisionGroup;
-use crate::level_instantiation::spawning::GameObject;
-use crate::movement::general_movement::{CharacterAnimations, CharacterControllerBundle, Model};
+use crate::level_instantiation::spawning::{
+    spawn_character, CharacterId, Characters, GameObject, ObjectProperties, SpawnData,
+};
 use crate::movement::navigation::Follower;
 use crate::world_interaction::dialog::{DialogId, DialogTarget};
+use anyhow::Result;
+use bevy::ecs::world::EntityRef;
 use bevy::prelude::*;
+use bevy_mod_sysfail::macros::*;
 use bevy_rapier3d::prelude::*;
-use std::f32::consts::TAU;
 
-pub const HEIGHT: f32 = 0.4;
-pub const RADIUS: f32 = 0.4;
+pub(crate) const DEFAULT_DIALOG: &str = "follower";
+pub(crate) const DEFAULT_CHARACTER: &str = "npc";
 
+pub(crate) fn read_properties(entity: EntityRef) -> ObjectProperties {
+    let mut properties = ObjectProperties::default();
+    if let Some(target) = entity.get::<DialogTarget>() {
+        properties.insert(target.dialog_id.clone());
+    }
+    if let Some(character) = entity.get::<CharacterId>() {
+        properties.insert(character.clone());
+    }
+    properties
+}
+
+#[sysfail(log(level = "error"))]
 pub(crate) fn spawn(
-    In(transform): In<Transform>,
+    In(SpawnData {
+        transform,
+        id,
+        properties,
+    }): In<SpawnData>,
     mut commands: Commands,
-    animations: Res<AnimationAssets>,
-    scene_handles: Res<SceneAssets>,
-) {
-    let entity = commands
-        .spawn((
-            PbrBundle {
-                transform,
-                ..default()
-            },
-            Name::new("NPC"),
-            CharacterControllerBundle::capsule(HEIGHT, RADIUS),
-            Follower,
-            CharacterAnimations {
-                idle: animations.character_idle.clone(),
-                walk: animations.character_walking.clone(),
-                aerial: animations.character_running.clone(),
-            },
-            DialogTarget {
-                dialog_id: DialogId::new("follower"),
-            },
-            GameObject::Npc,
-        ))
-        .with_children(|parent| {
-            parent.spawn((
-                Name::new("NPC Dialog Collider"),
-                Collider::cylinder(HEIGHT / 2., RADIUS * 5.),
-                Sensor,
-                ActiveEvents::COLLISION_EVENTS,
-                ActiveCollisionTypes::DYNAMIC_DYNAMIC,
-                CollisionGroups::new(
-                    GameCollisionGroup::OTHER.into(),
-                    GameCollisionGroup::PLAYER.into(),
-                ),
-            ));
-        })
-        .id();
-
-    commands
-        .spawn((
-            Model { target: entity },
-            SpatialBundle::default(),
-            Name::new("NPC Model Parent"),
-        ))
-        .with_children(|parent| {
-            parent.spawn((
-                SceneBundle {
-                    scene: scene_handles.character.clone(),
-                    transform: Transform {
-                        translation: Vec3::new(0., -HEIGHT / 2. - RADIUS, 0.),
-                        scale: Vec3::splat(0.012),
-                        rotation: Quat::from_rotation_y(TAU / 2.),
-                    },
-                    ..default()
-                },
-                Name::new("NPC Model"),
-            ));
-        });
+    characters: Characters,
+    assets: Res<NamedAssets>,
+) -> Result<()> {
+    let character = properties
+        .get::<CharacterId>()
+        .map_or(DEFAULT_CHARACTER, |character| character.0.as_str());
+    let definition = characters.get(character)?;
+    let (height, radius) = (definition.height, definition.radius);
+    spawn_character(
+        &mut commands,
+        &characters,
+        &assets,
+        character,
+        transform,
+        "NPC",
+    )?
+    .insert((
+        Follower,
+        DialogTarget {
+            dialog_id: properties
+                .get::<DialogId>()
+                .cloned()
+                .unwrap_or_else(|| DialogId::new(DEFAULT_DIALOG)),
+        },
+        GameObject::NPC,
+        id,
+    ))
+    .with_children(|parent| {
+        parent.spawn((
+            Name::new("NPC Dialog Collider"),
+            Collider::cylinder(height / 2., radius * 5.),
+            Sensor,
+            ActiveEvents::COLLISION_EVENTS,
+            ActiveCollisionTypes::DYNAMIC_DYNAMIC,
+            CollisionGroups::new(
+                GameCollisionGroup::OTHER.into(),
+                GameCollisionGroup::PLAYER.into(),
+            ),
+        ));
+    });
+    Ok(())
 }
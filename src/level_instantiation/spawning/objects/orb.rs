@@ -1,10 +1,27 @@
 use crate::level_instantiation::spawning::objects::util::MeshAssetsExt;
-use crate::level_instantiation::spawning::GameObject;
+use crate::level_instantiation::spawning::{GameObject, GlowColor, ObjectProperties, SpawnData};
 use crate::shader::Materials;
+use bevy::ecs::world::EntityRef;
 use bevy::pbr::{NotShadowCaster, NotShadowReceiver};
 use bevy::prelude::*;
 use bevy::reflect::TypeUuid;
 
+pub(crate) const DEFAULT_GLOW_COLOR: Color = Color::rgb(0.5, 0.1, 0.);
+
+/// The glow color is stored in the light the orb emits.
+pub(crate) fn read_properties(entity: EntityRef) -> ObjectProperties {
+    let world = entity.world();
+    let mut properties = ObjectProperties::default();
+    if let Some(light) = entity.get::<Children>().and_then(|children| {
+        children
+            .iter()
+            .find_map(|child| world.get::<PointLight>(*child))
+    }) {
+        properties.insert(GlowColor(light.color));
+    }
+    properties
+}
+
 fn get_or_add_mesh_handle(mesh_assets: &mut Assets<Mesh>) -> Handle<Mesh> {
     const MESH_HANDLE: HandleUntyped =
         HandleUntyped::weak_from_u64(Mesh::TYPE_UUID, 0x1f40128bac02a9b);
@@ -17,7 +34,11 @@ fn get_or_add_mesh_handle(mesh_assets: &mut Assets<Mesh>) -> Handle<Mesh> {
 }
 
 pub(crate) fn spawn(
-    In(transform): In<Transform>,
+    In(SpawnData {
+        transform,
+        id,
+        properties,
+    }): In<SpawnData>,
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     materials: Res<Materials>,
@@ -34,14 +55,17 @@ pub(crate) fn spawn(
             Name::new("Orb"),
             NotShadowCaster,
             NotShadowReceiver,
-            GameObject::Orb,
+            GameObject::ORB,
+            id,
         ))
         .with_children(|parent| {
             parent.spawn((PointLightBundle {
                 point_light: PointLight {
                     intensity: 10_000.,
                     radius: 1.,
-                    color: Color::rgb(0.5, 0.1, 0.),
+                    color: properties
+                        .get::<GlowColor>()
+                        .map_or(DEFAULT_GLOW_COLOR, |glow_color| glow_color.0),
                     shadows_enabled: true,
                     ..default()
                 },
@@ -1,22 +1,45 @@
-use crate::level_instantiation::spawning::GameObject;
+use crate::level_instantiation::spawning::{
+    GameObject, LightProperties, ObjectProperties, SpawnData,
+};
 
+use bevy::ecs::world::EntityRef;
 use bevy::prelude::*;
 
-pub(crate) fn spawn(In(transform): In<Transform>, mut commands: Commands) {
+pub(crate) fn read_properties(entity: EntityRef) -> ObjectProperties {
+    let mut properties = ObjectProperties::default();
+    if let Some(light) = entity.get::<PointLight>() {
+        properties.insert(LightProperties::from(light));
+    }
+    properties
+}
+
+pub(crate) fn spawn(
+    In(SpawnData {
+        transform,
+        id,
+        properties,
+    }): In<SpawnData>,
+    mut commands: Commands,
+) {
+    let light = properties
+        .get::<LightProperties>()
+        .cloned()
+        .unwrap_or_default();
     commands.spawn((
         PointLightBundle {
             point_light: PointLight {
-                color: Color::WHITE,
-                intensity: 1.0,
-                range: 1.0,
+                color: light.color,
+                intensity: light.intensity,
+                range: light.range,
                 radius: 1.0,
-                shadows_enabled: true,
+                shadows_enabled: light.shadows,
                 ..default()
             },
             transform,
             ..default()
         },
         Name::new("Light"),
-        GameObject::PointLight,
+        GameObject::POINT_LIGHT,
+        id,
     ));
 }
@@ -1,5 +1,5 @@
 use crate::level_instantiation::spawning::objects::util::MeshAssetsExt;
-use crate::level_instantiation::spawning::GameObject;
+use crate::level_instantiation::spawning::{GameObject, SpawnData};
 use crate::shader::Materials;
 
 use bevy::pbr::{NotShadowCaster, NotShadowReceiver};
@@ -23,7 +23,7 @@ fn get_or_add_mesh_handle(mesh_assets: &mut Assets<Mesh>) -> Handle<Mesh> {
 }
 
 pub(crate) fn spawn(
-    In(transform): In<Transform>,
+    In(SpawnData { transform, id, .. }): In<SpawnData>,
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     materials: Res<Materials>,
@@ -40,6 +40,7 @@ pub(crate) fn spawn(
             transform,
             ..default()
         },
-        GameObject::Skydome,
+        GameObject::SKYDOME,
+        id,
     ));
 }
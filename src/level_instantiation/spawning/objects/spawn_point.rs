@@ -0,0 +1,44 @@
+use crate::level_instantiation::spawning::{
+    GameObject, ObjectProperties, SpawnData, SpawnPointName,
+};
+use bevy::ecs::world::EntityRef;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// The spawn point the player starts at when a level is entered without naming one.
+pub const DEFAULT_SPAWN_POINT: &str = "start";
+
+/// A named location where the player arrives when entering a level.
+#[derive(Debug, Clone, PartialEq, Eq, Component, Reflect, Serialize, Deserialize, Default)]
+#[reflect(Component, Serialize, Deserialize)]
+pub struct SpawnPoint {
+    pub name: String,
+}
+
+pub(crate) fn read_properties(entity: EntityRef) -> ObjectProperties {
+    let mut properties = ObjectProperties::default();
+    if let Some(spawn_point) = entity.get::<SpawnPoint>() {
+        properties.insert(SpawnPointName(spawn_point.name.clone()));
+    }
+    properties
+}
+
+pub(crate) fn spawn(
+    In(SpawnData {
+        transform,
+        id,
+        properties,
+    }): In<SpawnData>,
+    mut commands: Commands,
+) {
+    let name = properties
+        .get::<SpawnPointName>()
+        .map_or_else(|| DEFAULT_SPAWN_POINT.to_owned(), |name| name.0.clone());
+    commands.spawn((
+        TransformBundle::from_transform(transform),
+        Name::new(format!("Spawn Point \"{name}\"")),
+        SpawnPoint { name },
+        GameObject::SPAWN_POINT,
+        id,
+    ));
+}
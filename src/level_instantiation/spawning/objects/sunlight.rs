@@ -1,9 +1,9 @@
-use crate::level_instantiation::spawning::GameObject;
+use crate::level_instantiation::spawning::{GameObject, SpawnData};
 
 use bevy::pbr::CascadeShadowConfigBuilder;
 use bevy::prelude::*;
 
-pub(crate) fn spawn(In(transform): In<Transform>, mut commands: Commands) {
+pub(crate) fn spawn(In(SpawnData { transform, id, .. }): In<SpawnData>, mut commands: Commands) {
     // directional 'sun' light
     commands.spawn((
         DirectionalLightBundle {
@@ -21,6 +21,7 @@ pub(crate) fn spawn(In(transform): In<Transform>, mut commands: Commands) {
             ..default()
         },
         Name::new("Light"),
-        GameObject::Sunlight,
+        GameObject::SUNLIGHT,
+        id,
     ));
 }
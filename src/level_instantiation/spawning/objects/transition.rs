@@ -0,0 +1,52 @@
+use crate::level_instantiation::spawning::objects::GameCollisionGroup;
+use crate::level_instantiation::spawning::{
+    GameObject, ObjectProperties, SpawnData, TransitionTarget,
+};
+use bevy::ecs::world::EntityRef;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A trigger volume that takes the player to a spawn point in another level when they enter it.
+/// Covers a unit cube, so its size is set through the scale of its transform.
+#[derive(Debug, Clone, PartialEq, Eq, Component, Reflect, Serialize, Deserialize, Default)]
+#[reflect(Component, Serialize, Deserialize)]
+pub struct Transition {
+    pub target: TransitionTarget,
+}
+
+pub(crate) fn read_properties(entity: EntityRef) -> ObjectProperties {
+    let mut properties = ObjectProperties::default();
+    if let Some(transition) = entity.get::<Transition>() {
+        properties.insert(transition.target.clone());
+    }
+    properties
+}
+
+pub(crate) fn spawn(
+    In(SpawnData {
+        transform,
+        id,
+        properties,
+    }): In<SpawnData>,
+    mut commands: Commands,
+) {
+    let Some(target) = properties.get::<TransitionTarget>().cloned() else {
+        error!("Failed to spawn transition {id:?}: No target level set in its properties");
+        return;
+    };
+    commands.spawn((
+        TransformBundle::from_transform(transform),
+        Collider::cuboid(0.5, 0.5, 0.5),
+        Sensor,
+        ActiveEvents::COLLISION_EVENTS,
+        CollisionGroups::new(
+            GameCollisionGroup::OTHER.into(),
+            GameCollisionGroup::PLAYER.into(),
+        ),
+        Name::new(format!("Transition to \"{}\"", target.level)),
+        Transition { target },
+        GameObject::TRANSITION,
+        id,
+    ));
+}
@@ -0,0 +1,94 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use serde::{Deserialize, Serialize};
+use spew::prelude::*;
+
+/// Identifies a [`GameObject`](super::GameObject) across sessions. Unlike an [`Entity`], this stays the same when
+/// the object is saved to a level or save file and loaded again, so it can be used to reference other objects in
+/// serialized data. Resolve it to an [`Entity`] with [`PersistentIdLookup`].
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    Hash,
+    Component,
+    Reflect,
+    FromReflect,
+    Serialize,
+    Deserialize,
+    Default,
+)]
+#[reflect(Component, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct PersistentId(pub u64);
+
+impl PersistentId {
+    /// Generates a new random ID.
+    pub fn new() -> Self {
+        Self(rand::random())
+    }
+
+    /// Deterministically combines the ID of a prefab instance with the ID of an object inside the prefab,
+    /// so that every instance of a prefab gets its own stable IDs.
+    pub fn nested(self, child: PersistentId) -> Self {
+        // SplitMix64 finalizer, so that similar inputs don't produce similar IDs.
+        let mut id = self.0.rotate_left(32) ^ child.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        id = (id ^ (id >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        id = (id ^ (id >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        Self(id ^ (id >> 31))
+    }
+}
+
+/// Maps every [`PersistentId`] in the world to the [`Entity`] carrying it.
+/// Updated right after spawning, so objects can be looked up in the same frame they were spawned in.
+#[derive(Debug, Clone, Resource, Default)]
+pub struct PersistentIdLookup {
+    entities: HashMap<PersistentId, Entity>,
+    ids: HashMap<Entity, PersistentId>,
+}
+
+impl PersistentIdLookup {
+    pub fn get(&self, id: PersistentId) -> Option<Entity> {
+        self.entities.get(&id).copied()
+    }
+
+    pub fn get_id(&self, entity: Entity) -> Option<PersistentId> {
+        self.ids.get(&entity).copied()
+    }
+}
+
+pub(super) fn persistent_id_plugin(app: &mut App) {
+    app.register_type::<PersistentId>()
+        .init_resource::<PersistentIdLookup>()
+        .add_system(
+            update_persistent_id_lookup
+                .in_set(PersistentIdLookupUpdate)
+                .after(SpewSystemSet),
+        );
+}
+
+/// Systems that resolve [`PersistentId`]s of freshly spawned objects should run after this set.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
+pub struct PersistentIdLookupUpdate;
+
+fn update_persistent_id_lookup(
+    mut lookup: ResMut<PersistentIdLookup>,
+    added_ids: Query<(Entity, &PersistentId), Changed<PersistentId>>,
+    mut removed_ids: RemovedComponents<PersistentId>,
+) {
+    for entity in removed_ids.iter() {
+        if let Some(id) = lookup.ids.remove(&entity) {
+            lookup.entities.remove(&id);
+        }
+    }
+    for (entity, id) in added_ids.iter() {
+        if let Some(old_id) = lookup.ids.insert(entity, *id) {
+            lookup.entities.remove(&old_id);
+        }
+        if let Some(other) = lookup.entities.insert(*id, entity) && other != entity {
+            warn!("Persistent ID {id:?} is used by both {other:?} and {entity:?}, only {entity:?} can be looked up");
+        }
+    }
+}
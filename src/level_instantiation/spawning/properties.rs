@@ -0,0 +1,466 @@
+use crate::level_instantiation::spawning::CharacterId;
+use crate::world_interaction::dialog::DialogId;
+use bevy::prelude::*;
+use bevy::reflect::{impl_from_reflect_value, impl_reflect_value};
+use serde::de::{DeserializeOwned, DeserializeSeed, MapAccess, Visitor};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::any::{Any, TypeId};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::RwLock;
+
+/// A typed value stored in [`ObjectProperties`]. Objects declare the properties they use with
+/// [`GameObjectRegistration::with_property`](super::GameObjectRegistration::with_property).
+pub trait ObjectProperty:
+    fmt::Debug + Clone + PartialEq + Serialize + DeserializeOwned + Send + Sync + 'static
+{
+    /// The name the property is written under in levels and saves, e.g. `"dialog"`.
+    const KEY: &'static str;
+}
+
+/// Per-object settings stored alongside a [`GameObject`](super::GameObject) in levels and saves and passed to
+/// its spawner. Holds at most one value per [`ObjectProperty`], keyed by [`ObjectProperty::KEY`].
+/// Missing properties are filled in from the object's
+/// [`GameObjectRegistration::default_properties`](super::GameObjectRegistration::default_properties),
+/// and spawners ignore properties that don't apply to them.
+///
+/// Serialized like a struct with one field per property, e.g. `(dialog: "follower", character: "npc")`.
+/// Only properties declared by a registered object can be deserialized.
+#[derive(Default)]
+pub struct ObjectProperties(BTreeMap<&'static str, Box<dyn PropertyValue>>);
+
+impl_reflect_value!(ObjectProperties(Debug, PartialEq, Serialize, Deserialize));
+impl_from_reflect_value!(ObjectProperties);
+
+impl ObjectProperties {
+    pub fn get<T: ObjectProperty>(&self) -> Option<&T> {
+        self.0.get(T::KEY)?.as_any().downcast_ref()
+    }
+
+    pub fn insert<T: ObjectProperty>(&mut self, value: T) {
+        self.0.insert(T::KEY, Box::new(value));
+    }
+
+    pub fn with<T: ObjectProperty>(mut self, value: T) -> Self {
+        self.insert(value);
+        self
+    }
+
+    pub fn remove<T: ObjectProperty>(&mut self) -> Option<T> {
+        let value = self.0.remove(T::KEY)?;
+        value.as_any().downcast_ref().cloned()
+    }
+
+    /// The [`ObjectProperty::KEY`]s of all properties that are set.
+    pub fn keys(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.0.keys().copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Fills in every property that is not set with the one from `defaults`.
+    pub fn with_defaults(mut self, defaults: &ObjectProperties) -> Self {
+        for (key, value) in defaults.0.iter() {
+            self.0.entry(key).or_insert_with(|| value.clone_box());
+        }
+        self
+    }
+}
+
+impl Clone for ObjectProperties {
+    fn clone(&self) -> Self {
+        Self(
+            self.0
+                .iter()
+                .map(|(key, value)| (*key, value.clone_box()))
+                .collect(),
+        )
+    }
+}
+
+impl PartialEq for ObjectProperties {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.len() == other.0.len()
+            && self.0.iter().all(|(key, value)| {
+                other
+                    .0
+                    .get(key)
+                    .map_or(false, |other| value.eq_value(other.as_ref()))
+            })
+    }
+}
+
+impl fmt::Debug for ObjectProperties {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.0.iter()).finish()
+    }
+}
+
+impl Serialize for ObjectProperties {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut properties = serializer.serialize_struct("ObjectProperties", self.0.len())?;
+        for (key, value) in self.0.iter() {
+            properties.serialize_field(key, value.as_serialize())?;
+        }
+        properties.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for ObjectProperties {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_struct("ObjectProperties", &[], PropertiesVisitor)
+    }
+}
+
+struct PropertiesVisitor;
+
+impl<'de> Visitor<'de> for PropertiesVisitor {
+    type Value = ObjectProperties;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("object properties")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut properties = ObjectProperties::default();
+        while let Some(PropertyKey(key)) = map.next_key()? {
+            let property_type = PropertyType::get(&key).ok_or_else(|| {
+                serde::de::Error::custom(format!("unknown object property \"{key}\""))
+            })?;
+            let value = map.next_value_seed(PropertySeed(property_type))?;
+            properties.0.insert(property_type.key, value);
+        }
+        Ok(properties)
+    }
+}
+
+/// Property keys are written like struct fields, which RON only reads as identifiers.
+struct PropertyKey(String);
+
+impl<'de> Deserialize<'de> for PropertyKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct KeyVisitor;
+
+        impl<'de> Visitor<'de> for KeyVisitor {
+            type Value = PropertyKey;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a property name")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, key: &str) -> Result<Self::Value, E> {
+                Ok(PropertyKey(key.to_owned()))
+            }
+        }
+
+        deserializer.deserialize_identifier(KeyVisitor)
+    }
+}
+
+/// Type-erased [`ObjectProperty`].
+trait PropertyValue: fmt::Debug + Send + Sync {
+    fn as_any(&self) -> &dyn Any;
+    fn clone_box(&self) -> Box<dyn PropertyValue>;
+    fn eq_value(&self, other: &dyn PropertyValue) -> bool;
+    fn as_serialize(&self) -> &dyn erased_serde::Serialize;
+}
+
+impl<T: ObjectProperty> PropertyValue for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn PropertyValue> {
+        Box::new(self.clone())
+    }
+
+    fn eq_value(&self, other: &dyn PropertyValue) -> bool {
+        other.as_any().downcast_ref::<T>() == Some(self)
+    }
+
+    fn as_serialize(&self) -> &dyn erased_serde::Serialize {
+        self
+    }
+}
+
+/// Levels and saves are deserialized by serde without access to the `World`, so the types of all declared
+/// properties are kept here instead of in the [`GameObjectRegistry`](super::GameObjectRegistry).
+static PROPERTY_TYPES: RwLock<BTreeMap<&'static str, PropertyType>> = RwLock::new(BTreeMap::new());
+
+/// How to deserialize an [`ObjectProperty`] found under its key.
+#[derive(Clone, Copy)]
+pub struct PropertyType {
+    pub key: &'static str,
+    type_id: TypeId,
+    type_name: &'static str,
+    deserialize: fn(
+        &mut dyn erased_serde::Deserializer,
+    ) -> Result<Box<dyn PropertyValue>, erased_serde::Error>,
+}
+
+impl PropertyType {
+    pub fn of<T: ObjectProperty>() -> Self {
+        Self {
+            key: T::KEY,
+            type_id: TypeId::of::<T>(),
+            type_name: std::any::type_name::<T>(),
+            deserialize: |deserializer| {
+                let value: T = erased_serde::deserialize(deserializer)?;
+                Ok(Box::new(value))
+            },
+        }
+    }
+
+    /// Makes the property deserializable. Returns `false` if another type already uses the same key,
+    /// in which case the existing one is kept.
+    pub(super) fn register(self) -> bool {
+        let mut property_types = PROPERTY_TYPES.write().unwrap();
+        match property_types.get(self.key) {
+            Some(existing) => existing.type_id == self.type_id,
+            None => {
+                property_types.insert(self.key, self);
+                true
+            }
+        }
+    }
+
+    fn get(key: &str) -> Option<Self> {
+        PROPERTY_TYPES.read().unwrap().get(key).copied()
+    }
+}
+
+impl fmt::Debug for PropertyType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PropertyType")
+            .field("key", &self.key)
+            .field("type_name", &self.type_name)
+            .finish_non_exhaustive()
+    }
+}
+
+struct PropertySeed(PropertyType);
+
+impl<'de> DeserializeSeed<'de> for PropertySeed {
+    type Value = Box<dyn PropertyValue>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        let mut deserializer = <dyn erased_serde::Deserializer>::erase(deserializer);
+        (self.0.deserialize)(&mut deserializer).map_err(serde::de::Error::custom)
+    }
+}
+
+/// The dialog started when talking to an NPC.
+impl ObjectProperty for DialogId {
+    const KEY: &'static str = "dialog";
+}
+
+/// The character definition an NPC is spawned from, e.g. `"npc"` for `characters/npc.char.ron`.
+impl ObjectProperty for CharacterId {
+    const KEY: &'static str = "character";
+}
+
+/// The color of the light emitted by an orb.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct GlowColor(pub Color);
+
+impl ObjectProperty for GlowColor {
+    const KEY: &'static str = "glow_color";
+}
+
+/// The name of a spawn point, referenced by `WorldLoadRequest::spawn_point`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SpawnPointName(pub String);
+
+impl ObjectProperty for SpawnPointName {
+    const KEY: &'static str = "name";
+}
+
+/// The named scene a level object imports, e.g. `"old_town"`.
+#[derive(Debug, Clone, PartialEq, Eq, Component, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct LevelScene(pub String);
+
+impl ObjectProperty for LevelScene {
+    const KEY: &'static str = "scene";
+}
+
+/// A spawn point in another level, e.g. `(level: "old_town", spawn_point: "start")`.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Reflect, FromReflect, Serialize, Deserialize)]
+#[reflect(Serialize, Deserialize)]
+pub struct TransitionTarget {
+    pub level: String,
+    pub spawn_point: String,
+}
+
+/// Where a transition volume leads to.
+impl ObjectProperty for TransitionTarget {
+    const KEY: &'static str = "transition";
+}
+
+/// Settings of a point light.
+#[derive(Debug, Clone, PartialEq, Reflect, FromReflect, Serialize, Deserialize)]
+#[reflect(Serialize, Deserialize)]
+#[serde(default)]
+pub struct LightProperties {
+    pub color: Color,
+    pub intensity: f32,
+    pub range: f32,
+    pub shadows: bool,
+}
+
+impl ObjectProperty for LightProperties {
+    const KEY: &'static str = "light";
+}
+
+impl Default for LightProperties {
+    fn default() -> Self {
+        Self {
+            color: Color::WHITE,
+            intensity: 1.0,
+            range: 1.0,
+            shadows: true,
+        }
+    }
+}
+
+impl From<&PointLight> for LightProperties {
+    fn from(light: &PointLight) -> Self {
+        Self {
+            color: light.color,
+            intensity: light.intensity,
+            range: light.range,
+            shadows: light.shadows_enabled,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_system_interaction::level_serialization::{Prefab, SerializedLevel};
+    use crate::level_instantiation::spawning::builtin_object_registry;
+
+    fn register_builtin_properties() {
+        builtin_object_registry();
+    }
+
+    fn example() -> ObjectProperties {
+        ObjectProperties::default()
+            .with(DialogId::new("follower"))
+            .with(GlowColor(Color::rgba(0.1, 0.3, 0.8, 1.0)))
+            .with(LightProperties {
+                intensity: 800.0,
+                ..default()
+            })
+    }
+
+    #[test]
+    fn properties_are_typed() {
+        let properties = example();
+        assert_eq!(
+            properties.get::<DialogId>(),
+            Some(&DialogId::new("follower"))
+        );
+        assert_eq!(
+            properties
+                .get::<LightProperties>()
+                .map(|light| light.intensity),
+            Some(800.0)
+        );
+        assert_eq!(properties.get::<SpawnPointName>(), None);
+        assert_eq!(
+            properties.keys().collect::<Vec<_>>(),
+            ["dialog", "glow_color", "light"]
+        );
+    }
+
+    #[test]
+    fn defaults_only_fill_in_missing_properties() {
+        let defaults = ObjectProperties::default()
+            .with(DialogId::new("default"))
+            .with(CharacterId("npc".to_owned()));
+        let properties = ObjectProperties::default()
+            .with(DialogId::new("custom"))
+            .with_defaults(&defaults);
+        assert_eq!(properties.get::<DialogId>(), Some(&DialogId::new("custom")));
+        assert_eq!(
+            properties.get::<CharacterId>(),
+            Some(&CharacterId("npc".to_owned()))
+        );
+    }
+
+    #[test]
+    fn ron_round_trip() {
+        register_builtin_properties();
+        let properties = example();
+        let serialized = ron::to_string(&properties).unwrap();
+        let deserialized: ObjectProperties = ron::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, properties);
+    }
+
+    #[test]
+    fn messagepack_round_trip() {
+        register_builtin_properties();
+        let properties = example();
+        let serialized = rmp_serde::to_vec_named(&properties).unwrap();
+        let deserialized: ObjectProperties = rmp_serde::from_slice(&serialized).unwrap();
+        assert_eq!(deserialized, properties);
+    }
+
+    #[test]
+    fn reads_properties_written_as_struct_fields() {
+        register_builtin_properties();
+        let properties: ObjectProperties = ron::from_str(
+            r#"(name: "start", transition: (level: "old_town", spawn_point: "start"))"#,
+        )
+        .unwrap();
+        assert_eq!(
+            properties.get::<SpawnPointName>(),
+            Some(&SpawnPointName("start".to_owned()))
+        );
+        assert_eq!(
+            properties
+                .get::<TransitionTarget>()
+                .map(|target| target.level.as_str()),
+            Some("old_town")
+        );
+    }
+
+    #[test]
+    fn unknown_properties_are_rejected() {
+        register_builtin_properties();
+        let error = ron::from_str::<ObjectProperties>(r#"(flavor: "vanilla")"#).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("unknown object property \"flavor\""),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn key_taken_by_another_type_is_not_registered() {
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+        struct OtherDialog(String);
+        impl ObjectProperty for OtherDialog {
+            const KEY: &'static str = DialogId::KEY;
+        }
+
+        register_builtin_properties();
+        assert!(PropertyType::of::<DialogId>().register());
+        assert!(!PropertyType::of::<OtherDialog>().register());
+    }
+
+    #[test]
+    fn bundled_levels_and_prefabs_parse() {
+        register_builtin_properties();
+        ron::from_str::<SerializedLevel>(include_str!("../../../assets/levels/old_town.lvl.ron"))
+            .unwrap();
+        ron::from_str::<Prefab>(include_str!("../../../assets/prefabs/shrine.pfb.ron")).unwrap();
+    }
+}
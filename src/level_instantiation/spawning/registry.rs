@@ -0,0 +1,203 @@
+use crate::level_instantiation::spawning::{
+    GameObject, ObjectProperties, ObjectProperty, PropertyType, SpawnData,
+};
+use bevy::ecs::world::EntityRef;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use spew::prelude::*;
+use std::fmt;
+
+/// Describes a kind of [`GameObject`] that can be placed in levels. Register it with
+/// [`GameObjectRegistryExt::register_game_object`].
+#[derive(Clone)]
+pub struct GameObjectRegistration {
+    pub object: GameObject,
+    /// The properties the object uses. Only these are read by its spawner and written to levels.
+    pub property_types: Vec<PropertyType>,
+    /// Used for every property a level entry or spawn request leaves out.
+    pub default_properties: ObjectProperties,
+    /// Reads the properties of a spawned object back from its components when the level is saved.
+    pub read_properties: fn(EntityRef) -> ObjectProperties,
+    /// Whether the object is written to levels. Objects like the player are spawned by the game instead.
+    pub saved_in_levels: bool,
+    /// Whether changes to the object are written to saves. Objects with their own save state, like the camera,
+    /// opt out of this.
+    pub saved_in_saves: bool,
+    /// Whether the object is only spawned while the cell it is in is loaded, see
+    /// [`LevelStreaming`](crate::level_instantiation::streaming::LevelStreaming).
+    /// Objects that affect the whole level, like the sun, opt out of this.
+    pub streamed: bool,
+}
+
+impl fmt::Debug for GameObjectRegistration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GameObjectRegistration")
+            .field("object", &self.object)
+            .field("property_types", &self.property_types)
+            .field("default_properties", &self.default_properties)
+            .field("saved_in_levels", &self.saved_in_levels)
+            .field("saved_in_saves", &self.saved_in_saves)
+            .field("streamed", &self.streamed)
+            .finish_non_exhaustive()
+    }
+}
+
+impl GameObjectRegistration {
+    pub fn new(object: GameObject) -> Self {
+        Self {
+            object,
+            property_types: default(),
+            default_properties: default(),
+            read_properties: |_| default(),
+            saved_in_levels: true,
+            saved_in_saves: true,
+            streamed: true,
+        }
+    }
+
+    /// Declares a property the object uses without a default value.
+    pub fn with_property<T: ObjectProperty>(mut self) -> Self {
+        if !self.uses_property(T::KEY) {
+            self.property_types.push(PropertyType::of::<T>());
+        }
+        self
+    }
+
+    /// Declares a property the object uses, which is set to `value` unless a level entry or spawn request sets it.
+    pub fn with_default_property<T: ObjectProperty>(mut self, value: T) -> Self {
+        self.default_properties.insert(value);
+        self.with_property::<T>()
+    }
+
+    pub fn uses_property(&self, key: &str) -> bool {
+        self.property_types
+            .iter()
+            .any(|property_type| property_type.key == key)
+    }
+
+    pub fn with_property_reader(
+        mut self,
+        read_properties: fn(EntityRef) -> ObjectProperties,
+    ) -> Self {
+        self.read_properties = read_properties;
+        self
+    }
+
+    /// Spawned along with the level regardless of where the player is.
+    pub fn always_loaded(mut self) -> Self {
+        self.streamed = false;
+        self
+    }
+
+    /// Neither written to levels nor tracked in saves.
+    pub fn unsaved(mut self) -> Self {
+        self.saved_in_levels = false;
+        self.saved_in_saves = false;
+        self
+    }
+}
+
+/// All kinds of [`GameObject`]s known to the game, in the order they were registered.
+#[derive(Debug, Clone, Resource, Default)]
+pub struct GameObjectRegistry {
+    registrations: Vec<GameObjectRegistration>,
+    indices: HashMap<GameObject, usize>,
+}
+
+impl GameObjectRegistry {
+    pub fn get(&self, object: &GameObject) -> Option<&GameObjectRegistration> {
+        self.indices
+            .get(object)
+            .map(|index| &self.registrations[*index])
+    }
+
+    pub fn contains(&self, object: &GameObject) -> bool {
+        self.indices.contains_key(object)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &GameObjectRegistration> {
+        self.registrations.iter()
+    }
+
+    /// Creates a spawn request, filling in the object's default properties.
+    /// Returns `None` if the object is not registered.
+    pub fn spawn_event(
+        &self,
+        object: &GameObject,
+        data: SpawnData,
+    ) -> Option<SpawnEvent<GameObject, SpawnData>> {
+        let registration = self.get(object)?;
+        let properties = data
+            .properties
+            .clone()
+            .with_defaults(&registration.default_properties);
+        Some(SpawnEvent::with_data(
+            object.clone(),
+            data.with_properties(properties),
+        ))
+    }
+
+    pub fn is_saved_in_levels(&self, object: &GameObject) -> bool {
+        self.get(object)
+            .map_or(false, |registration| registration.saved_in_levels)
+    }
+
+    pub fn is_saved_in_saves(&self, object: &GameObject) -> bool {
+        self.get(object)
+            .map_or(false, |registration| registration.saved_in_saves)
+    }
+
+    pub fn is_streamed(&self, object: &GameObject) -> bool {
+        self.get(object)
+            .map_or(false, |registration| registration.streamed)
+    }
+
+    /// Returns `false` if the object was already registered, in which case the registry is left unchanged.
+    fn insert(&mut self, registration: GameObjectRegistration) -> bool {
+        if self.contains(&registration.object) {
+            return false;
+        }
+        self.indices
+            .insert(registration.object.clone(), self.registrations.len());
+        self.registrations.push(registration);
+        true
+    }
+}
+
+pub trait GameObjectRegistryExt {
+    /// Registers a kind of [`GameObject`] along with the system that spawns it.
+    fn register_game_object<M: Send + Sync + 'static>(
+        &mut self,
+        registration: GameObjectRegistration,
+        spawner: impl SystemParamFunction<M, In = SpawnData, Out = ()>,
+    ) -> &mut Self;
+}
+
+impl GameObjectRegistryExt for App {
+    fn register_game_object<M: Send + Sync + 'static>(
+        &mut self,
+        registration: GameObjectRegistration,
+        spawner: impl SystemParamFunction<M, In = SpawnData, Out = ()>,
+    ) -> &mut Self {
+        let object = registration.object.clone();
+        let property_types = registration.property_types.clone();
+        let inserted = self
+            .world
+            .get_resource_or_insert_with(GameObjectRegistry::default)
+            .insert(registration);
+        if !inserted {
+            warn!("Game object \"{object}\" is already registered, ignoring the new registration");
+            return self;
+        }
+        for property_type in property_types {
+            if !property_type.register() {
+                warn!(
+                    "Property \"{}\" of game object \"{object}\" is already registered with a different type, \
+                     keeping the existing one",
+                    property_type.key
+                );
+            }
+        }
+        self.add_spawner((object, spawner))
+    }
+}
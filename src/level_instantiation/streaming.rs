@@ -0,0 +1,436 @@
+use crate::file_system_interaction::game_state_serialization::{CharacterMotion, PendingMotions};
+use crate::file_system_interaction::level_serialization::LevelObject;
+use crate::level_instantiation::spawning::{GameObject, GameObjectRegistry, PersistentId};
+use crate::movement::general_movement::Walking;
+use crate::player_control::player_embodiment::Player;
+use crate::GameState;
+use bevy::prelude::*;
+use bevy::render::primitives::Aabb;
+use bevy::utils::{HashMap, HashSet};
+use bevy_rapier3d::prelude::*;
+use oxidized_navigation::NavMeshAffector;
+use serde::{Deserialize, Serialize};
+use warbler_grass::prelude::*;
+
+/// Loads and unloads the cells of levels with [`StreamingSettings`] around the player.
+pub fn streaming_plugin(app: &mut App) {
+    app.add_systems(
+        (
+            plan_cell_changes.run_if(any_with_component::<Player>()),
+            apply_cell_changes.run_if(has_pending_cell_changes),
+            stream_colliders,
+            stream_grass,
+        )
+            .chain()
+            .distributive_run_if(resource_exists::<LevelStreaming>())
+            .in_set(LevelStreamingSystemSet)
+            .in_set(OnUpdate(GameState::Playing)),
+    );
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
+pub struct LevelStreamingSystemSet;
+
+/// Splits a level into square cells on the XZ plane that are only loaded while the player is near them.
+/// Cells closer than `load_distance` are loaded and cells further away than `unload_distance` are unloaded,
+/// so that walking along the border of a cell does not load and unload it over and over.
+#[derive(Debug, Clone, PartialEq, Reflect, FromReflect, Serialize, Deserialize)]
+#[reflect(Serialize, Deserialize)]
+pub struct StreamingSettings {
+    pub cell_size: f32,
+    pub load_distance: f32,
+    pub unload_distance: f32,
+    /// The level spans this many cells from the origin in every direction on the XZ plane.
+    /// Cells outside of it are never loaded and the navmesh covers exactly this area.
+    #[serde(default = "get_default_bounds_in_cells")]
+    pub bounds_in_cells: u32,
+}
+
+fn get_default_bounds_in_cells() -> u32 {
+    16
+}
+
+impl StreamingSettings {
+    /// Distance from the origin to the border of the level along X and Z.
+    pub fn half_extents(&self) -> f32 {
+        self.cell_size.max(1.0) * self.bounds_in_cells as f32
+    }
+}
+
+/// An object of a cell that is not loaded, as it was when the cell was unloaded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnloadedObject {
+    pub object: LevelObject,
+    /// Restored once the cell is loaded again.
+    pub motion: Option<CharacterMotion>,
+}
+
+impl From<LevelObject> for UnloadedObject {
+    fn from(object: LevelObject) -> Self {
+        Self {
+            object,
+            motion: None,
+        }
+    }
+}
+
+/// The cells of the current level. Only exists while a level with [`StreamingSettings`] is loaded.
+#[derive(Debug, Clone, Resource)]
+pub struct LevelStreaming {
+    settings: StreamingSettings,
+    loaded_cells: HashSet<IVec2>,
+    pending_loads: HashSet<IVec2>,
+    pending_unloads: HashSet<IVec2>,
+    /// Objects of the cells that are not loaded.
+    unloaded_objects: HashMap<PersistentId, UnloadedObject>,
+}
+
+impl LevelStreaming {
+    /// Starts out with no cells loaded.
+    pub fn new(settings: StreamingSettings) -> Self {
+        let cell_size = settings.cell_size.max(1.0);
+        let settings = StreamingSettings {
+            cell_size,
+            load_distance: settings.load_distance.max(0.0),
+            unload_distance: settings.unload_distance.max(settings.load_distance),
+            bounds_in_cells: settings.bounds_in_cells,
+        };
+        Self {
+            settings,
+            loaded_cells: default(),
+            pending_loads: default(),
+            pending_unloads: default(),
+            unloaded_objects: default(),
+        }
+    }
+
+    pub fn settings(&self) -> &StreamingSettings {
+        &self.settings
+    }
+
+    pub fn cell_of(&self, translation: Vec3) -> IVec2 {
+        (Vec2::new(translation.x, translation.z) / self.settings.cell_size)
+            .floor()
+            .as_ivec2()
+    }
+
+    /// Whether the cell lies within [`StreamingSettings::bounds_in_cells`].
+    pub fn is_in_bounds(&self, cell: IVec2) -> bool {
+        let bounds = self.settings.bounds_in_cells as i32;
+        (-bounds..bounds).contains(&cell.x) && (-bounds..bounds).contains(&cell.y)
+    }
+
+    pub fn is_loaded(&self, translation: Vec3) -> bool {
+        self.loaded_cells.contains(&self.cell_of(translation))
+    }
+
+    /// Whether any cell touching the area between `min` and `max` on the XZ plane is loaded.
+    pub fn is_any_loaded(&self, min: Vec3, max: Vec3) -> bool {
+        let (min, max) = (self.cell_of(min), self.cell_of(max));
+        (min.x..=max.x)
+            .flat_map(|x| (min.y..=max.y).map(move |y| IVec2::new(x, y)))
+            .any(|cell| self.loaded_cells.contains(&cell))
+    }
+
+    pub fn unloaded_objects(&self) -> impl Iterator<Item = &UnloadedObject> {
+        self.unloaded_objects.values()
+    }
+
+    /// Keeps an object until its cell is loaded. Objects in cells that are already loaded are returned instead,
+    /// so that the caller can spawn them right away.
+    pub fn store(&mut self, unloaded: UnloadedObject) -> Option<UnloadedObject> {
+        if self.is_loaded(unloaded.object.transform.translation) {
+            return Some(unloaded);
+        }
+        self.unloaded_objects.insert(unloaded.object.id, unloaded);
+        None
+    }
+
+    /// Takes an object out of an unloaded cell, e.g. to despawn it for good.
+    pub fn take(&mut self, id: PersistentId) -> Option<UnloadedObject> {
+        self.unloaded_objects.remove(&id)
+    }
+
+    /// Distance on the XZ plane from `translation` to the closest point of `cell`.
+    fn distance_to_cell(&self, translation: Vec3, cell: IVec2) -> f32 {
+        let point = Vec2::new(translation.x, translation.z);
+        let min = cell.as_vec2() * self.settings.cell_size;
+        let max = min + Vec2::splat(self.settings.cell_size);
+        point.clamp(min, max).distance(point)
+    }
+}
+
+fn plan_cell_changes(
+    mut streaming: ResMut<LevelStreaming>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    let Some(player) = player_query.iter().next() else {
+        return;
+    };
+    let translation = player.translation;
+    let settings = streaming.settings.clone();
+    let radius = (settings.load_distance / settings.cell_size).ceil() as i32;
+    let center = streaming.cell_of(translation);
+
+    let loads: HashSet<_> = (-radius..=radius)
+        .flat_map(|x| (-radius..=radius).map(move |y| center + IVec2::new(x, y)))
+        .filter(|cell| !streaming.loaded_cells.contains(cell))
+        .filter(|cell| streaming.is_in_bounds(*cell))
+        .filter(|cell| streaming.distance_to_cell(translation, *cell) <= settings.load_distance)
+        .collect();
+    let unloads: HashSet<_> = streaming
+        .loaded_cells
+        .iter()
+        .filter(|cell| streaming.distance_to_cell(translation, **cell) > settings.unload_distance)
+        .copied()
+        .collect();
+    if !loads.is_empty() || !unloads.is_empty() {
+        streaming.pending_loads = loads;
+        streaming.pending_unloads = unloads;
+    }
+}
+
+fn has_pending_cell_changes(streaming: Res<LevelStreaming>) -> bool {
+    !streaming.pending_loads.is_empty() || !streaming.pending_unloads.is_empty()
+}
+
+/// Despawns the objects of unloaded cells, remembering their current state, and spawns the ones of loaded cells.
+fn apply_cell_changes(world: &mut World) {
+    world.resource_scope(|world, mut streaming: Mut<LevelStreaming>| {
+        let unloads = std::mem::take(&mut streaming.pending_unloads);
+        let loads = std::mem::take(&mut streaming.pending_loads);
+
+        let mut query = world.query::<(
+            Entity,
+            &PersistentId,
+            &GameObject,
+            &Transform,
+            Option<&Velocity>,
+            Option<&Walking>,
+        )>();
+        let registry = world.resource::<GameObjectRegistry>();
+        let unloaded: Vec<_> = query
+            .iter(world)
+            .filter(|(_, _, _, transform, ..)| {
+                unloads.contains(&streaming.cell_of(transform.translation))
+            })
+            .filter_map(|(entity, id, object, transform, velocity, walking)| {
+                let registration = registry.get(object)?;
+                registration.streamed.then(|| {
+                    let object = LevelObject {
+                        id: *id,
+                        object: object.clone(),
+                        transform: *transform,
+                        properties: (registration.read_properties)(world.entity(entity)),
+                    };
+                    let motion = CharacterMotion::read(velocity, walking);
+                    (entity, UnloadedObject { object, motion })
+                })
+            })
+            .collect();
+        for (entity, unloaded) in unloaded {
+            world.entity_mut(entity).despawn_recursive();
+            streaming
+                .unloaded_objects
+                .insert(unloaded.object.id, unloaded);
+        }
+        for cell in unloads.iter() {
+            streaming.loaded_cells.remove(cell);
+        }
+
+        streaming.loaded_cells.extend(loads.iter().copied());
+        let loaded: Vec<_> = streaming
+            .unloaded_objects
+            .values()
+            .map(|unloaded| &unloaded.object)
+            .filter(|object| loads.contains(&streaming.cell_of(object.transform.translation)))
+            .map(|object| object.id)
+            .collect();
+        let loaded: Vec<_> = loaded
+            .into_iter()
+            .filter_map(|id| streaming.unloaded_objects.remove(&id))
+            .collect();
+        let registry = world.resource::<GameObjectRegistry>();
+        let spawn_events: Vec<_> = loaded
+            .iter()
+            .filter_map(|unloaded| unloaded.object.spawn_event(registry))
+            .collect();
+        for event in spawn_events {
+            world.send_event(event);
+        }
+        let mut pending_motions = world.get_resource_or_insert_with(PendingMotions::default);
+        for unloaded in loaded {
+            if let Some(motion) = unloaded.motion {
+                pending_motions.insert(unloaded.object.id, motion);
+            }
+        }
+    });
+}
+
+/// Collider of a part of the level scene. It is only active while a cell it touches is loaded.
+#[derive(Debug, Clone, Component)]
+pub struct StreamedCollider(pub Collider);
+
+fn stream_colliders(
+    mut commands: Commands,
+    streaming: Res<LevelStreaming>,
+    colliders: Query<(
+        Entity,
+        &StreamedCollider,
+        &GlobalTransform,
+        Option<&Aabb>,
+        Option<&Collider>,
+    )>,
+) {
+    for (entity, streamed, transform, aabb, collider) in colliders.iter() {
+        let (center, radius) = aabb.map_or((Vec3::ZERO, 0.), |aabb| {
+            (aabb.center.into(), Vec3::from(aabb.half_extents).length())
+        });
+        let center = transform.transform_point(center);
+        let radius = radius * transform.compute_transform().scale.max_element();
+        let extents = Vec3::splat(radius);
+        let loaded = streaming.is_any_loaded(center - extents, center + extents);
+        match (loaded, collider.is_some()) {
+            (true, false) => {
+                commands
+                    .entity(entity)
+                    .insert((streamed.0.clone(), NavMeshAffector::default()));
+            }
+            (false, true) => {
+                commands
+                    .entity(entity)
+                    .remove::<(Collider, NavMeshAffector)>();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Grass growing in a single cell. It is only shown while the cell is loaded.
+#[derive(Debug, Clone, Component)]
+pub struct StreamedGrass {
+    pub cell: IVec2,
+    pub grass: Grass,
+}
+
+fn stream_grass(
+    mut commands: Commands,
+    streaming: Res<LevelStreaming>,
+    grass_query: Query<(Entity, &StreamedGrass, Option<&Grass>)>,
+) {
+    for (entity, streamed, grass) in grass_query.iter() {
+        match (
+            streaming.loaded_cells.contains(&streamed.cell),
+            grass.is_some(),
+        ) {
+            (true, false) => {
+                commands.entity(entity).insert(streamed.grass.clone());
+            }
+            (false, true) => {
+                commands.entity(entity).remove::<Grass>();
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level_instantiation::spawning::{builtin_object_registry, SpawnData};
+    use crate::movement::navigation::nav_mesh_settings;
+    use spew::prelude::*;
+
+    const NPC_ID: PersistentId = PersistentId(1);
+
+    fn settings() -> StreamingSettings {
+        StreamingSettings {
+            cell_size: 10.0,
+            load_distance: 15.0,
+            unload_distance: 25.0,
+            bounds_in_cells: 4,
+        }
+    }
+
+    fn world_with_streaming() -> World {
+        let mut world = World::new();
+        world.insert_resource(builtin_object_registry());
+        world.init_resource::<Events<SpawnEvent<GameObject, SpawnData>>>();
+        let mut streaming = LevelStreaming::new(settings());
+        streaming.loaded_cells.insert(IVec2::ZERO);
+        world.insert_resource(streaming);
+        world
+    }
+
+    fn motion() -> CharacterMotion {
+        CharacterMotion {
+            velocity: Velocity::linear(Vec3::X),
+            walking: Walking {
+                direction: Some(Vec3::X),
+                ..default()
+            },
+        }
+    }
+
+    #[test]
+    fn navmesh_covers_the_streaming_bounds() {
+        assert_eq!(settings().half_extents(), 40.0);
+        assert_eq!(
+            nav_mesh_settings(Some(&settings())).world_half_extents,
+            40.0
+        );
+    }
+
+    #[test]
+    fn cells_outside_of_the_bounds_are_not_loaded() {
+        let streaming = LevelStreaming::new(settings());
+        assert!(streaming.is_in_bounds(IVec2::new(-4, 3)));
+        assert!(!streaming.is_in_bounds(IVec2::new(4, 0)));
+        assert!(!streaming.is_in_bounds(IVec2::new(0, -5)));
+    }
+
+    #[test]
+    fn motion_survives_unloading_and_loading_a_cell() {
+        let mut world = world_with_streaming();
+        let velocity = motion().velocity;
+        let walking = motion().walking;
+        let npc = world
+            .spawn((
+                NPC_ID,
+                GameObject::NPC,
+                Transform::from_xyz(5.0, 0.0, 5.0),
+                velocity,
+                walking,
+            ))
+            .id();
+
+        world
+            .resource_mut::<LevelStreaming>()
+            .pending_unloads
+            .insert(IVec2::ZERO);
+        apply_cell_changes(&mut world);
+        assert!(world.get_entity(npc).is_none());
+        let unloaded = world
+            .resource::<LevelStreaming>()
+            .unloaded_objects
+            .get(&NPC_ID)
+            .cloned()
+            .unwrap();
+        assert_eq!(unloaded.motion, Some(motion()));
+
+        world
+            .resource_mut::<LevelStreaming>()
+            .pending_loads
+            .insert(IVec2::ZERO);
+        apply_cell_changes(&mut world);
+        assert_eq!(
+            world
+                .resource::<Events<SpawnEvent<GameObject, SpawnData>>>()
+                .len(),
+            1
+        );
+        let mut expected = PendingMotions::default();
+        expected.insert(NPC_ID, motion());
+        assert_eq!(world.resource::<PendingMotions>(), &expected);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::file_system_interaction::config::GameConfig;
+use crate::file_system_interaction::user_settings::UserSettings;
 use crate::GameState;
 use bevy::prelude::*;
 use bevy_egui::egui::FontFamily::Proportional;
@@ -5,13 +7,22 @@ use bevy_egui::egui::FontId;
 use bevy_egui::egui::TextStyle::{Button, Heading};
 use bevy_egui::{egui, EguiContexts};
 
+mod options;
+pub use options::show_options;
+
 /// This plugin is responsible for the game menu
 /// The menu is only drawn during the State `GameState::Menu` and is removed when that state is exited.
 pub fn menu_plugin(app: &mut App) {
     app.add_system(setup_menu.in_set(OnUpdate(GameState::Menu)));
 }
 
-fn setup_menu(mut egui_contexts: EguiContexts, mut next_state: ResMut<NextState<GameState>>) {
+fn setup_menu(
+    mut egui_contexts: EguiContexts,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut showing_options: Local<bool>,
+    settings: Option<ResMut<UserSettings>>,
+    config: Option<Res<GameConfig>>,
+) {
     get_menu_panel().show(egui_contexts.ctx_mut(), |ui| {
         set_menu_style(ui.style_mut());
         ui.vertical_centered_justified(|ui| {
@@ -19,9 +30,22 @@ fn setup_menu(mut egui_contexts: EguiContexts, mut next_state: ResMut<NextState<
             ui.heading("Foxtrot");
             ui.separator();
             ui.add_space(50.);
+            if let (true, Some(mut settings), Some(config)) = (*showing_options, settings, config) {
+                if let Some(edited) = show_options(ui, &settings, &config) {
+                    *settings = edited;
+                }
+                ui.add_space(30.);
+                if ui.button("Back").clicked() {
+                    *showing_options = false;
+                }
+                return;
+            }
             if ui.button("Play").clicked() {
                 next_state.set(GameState::Playing);
             }
+            if ui.button("Options").clicked() {
+                *showing_options = true;
+            }
         })
     });
 }
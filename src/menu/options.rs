@@ -0,0 +1,108 @@
+use crate::file_system_interaction::config::GameConfig;
+use crate::file_system_interaction::user_settings::UserSettings;
+use bevy_egui::egui;
+use std::ops::RangeInclusive;
+
+/// Shows sliders for the [`UserSettings`], starting at the values of the current `config`.
+/// Returns the edited settings if the player changed anything, so that callers only mark the resource
+/// as changed when needed.
+pub fn show_options(
+    ui: &mut egui::Ui,
+    settings: &UserSettings,
+    config: &GameConfig,
+) -> Option<UserSettings> {
+    let mut edited = settings.clone();
+    egui::Grid::new("options")
+        .num_columns(2)
+        .spacing([40.0, 10.0])
+        .show(ui, |ui| {
+            setting_slider(
+                ui,
+                "Horizontal mouse sensitivity",
+                &mut edited.mouse_sensitivity_x,
+                config.camera.mouse_sensitivity_x,
+                1e-4..=3e-3,
+            );
+            setting_slider(
+                ui,
+                "Vertical mouse sensitivity",
+                &mut edited.mouse_sensitivity_y,
+                config.camera.mouse_sensitivity_y,
+                1e-4..=3e-3,
+            );
+            degrees_slider(
+                ui,
+                "Minimum field of view",
+                &mut edited.min_fov,
+                config.player.min_fov,
+            );
+            degrees_slider(
+                ui,
+                "Maximum field of view",
+                &mut edited.max_fov,
+                config.player.max_fov,
+            );
+            setting_slider(
+                ui,
+                "Text speed",
+                &mut edited.letters_per_second,
+                config.dialog.base_letters_per_second,
+                10.0..=200.0,
+            );
+            setting_slider(
+                ui,
+                "Volume",
+                &mut edited.volume,
+                config.audio.volume,
+                0.0..=1.0,
+            );
+        });
+
+    // Keep the field of view range valid while dragging either end
+    let min_fov = edited.min_fov.unwrap_or(config.player.min_fov);
+    let max_fov = edited.max_fov.unwrap_or(config.player.max_fov);
+    if min_fov > max_fov {
+        if edited.min_fov != settings.min_fov {
+            edited.max_fov = Some(min_fov);
+        } else {
+            edited.min_fov = Some(max_fov);
+        }
+    }
+    ui.add_space(10.0);
+    if ui.button("Reset to defaults").clicked() {
+        edited = UserSettings::default();
+    }
+    (edited != *settings).then_some(edited)
+}
+
+fn setting_slider(
+    ui: &mut egui::Ui,
+    label: &str,
+    setting: &mut Option<f32>,
+    current: f32,
+    range: RangeInclusive<f32>,
+) {
+    ui.label(label);
+    let mut value = setting.unwrap_or(current);
+    let logarithmic = *range.start() > 0.0 && range.end() / range.start() > 100.0;
+    if ui
+        .add(egui::Slider::new(&mut value, range).logarithmic(logarithmic))
+        .changed()
+    {
+        *setting = Some(value);
+    }
+    ui.end_row();
+}
+
+/// Field of view is stored in radians, but players think in degrees.
+fn degrees_slider(ui: &mut egui::Ui, label: &str, setting: &mut Option<f32>, current: f32) {
+    ui.label(label);
+    let mut degrees = setting.unwrap_or(current).to_degrees();
+    if ui
+        .add(egui::Slider::new(&mut degrees, 30.0..=150.0).suffix("°"))
+        .changed()
+    {
+        *setting = Some(degrees.to_radians());
+    }
+    ui.end_row();
+}
@@ -60,6 +60,7 @@ pub struct Model {
 
 #[derive(Debug, Clone, PartialEq, Component, Reflect, Serialize, Deserialize)]
 #[reflect(Component, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Walking {
     /// Acceleration on the ground
     pub ground_acceleration: f32,
@@ -112,6 +113,7 @@ pub struct Grounded(pub bool);
 
 #[derive(Debug, Clone, PartialEq, Component, Reflect, Serialize, Deserialize)]
 #[reflect(Component, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Jumping {
     /// Speed of the jump in m/s
     pub speed: f32,
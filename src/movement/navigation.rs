@@ -1,6 +1,6 @@
 #[cfg(feature = "dev")]
 use crate::dev::dev_editor::DevEditorWindow;
-use crate::level_instantiation::spawning::objects::npc;
+use crate::level_instantiation::streaming::StreamingSettings;
 use crate::movement::general_movement::{GeneralMovementSystemSet, Walking};
 use crate::player_control::player_embodiment::Player;
 use crate::util::trait_extension::{F32Ext, Vec3Ext};
@@ -18,28 +18,16 @@ use oxidized_navigation::{
 };
 use serde::{Deserialize, Serialize};
 
-/// Manually tweaked
-const CELL_WIDTH: f32 = 0.4 * npc::RADIUS;
+/// Manually tweaked for characters with a radius of about 0.4
+const CELL_WIDTH: f32 = 0.16;
+
+/// Levels without [`StreamingSettings`] have to fit into this area.
+const DEFAULT_WORLD_HALF_EXTENTS: f32 = 250.0;
 
 /// Handles NPC pathfinding. Currently, all entities with the [`Follower`] component will follow the [`Player`].
 pub fn navigation_plugin(app: &mut App) {
     app.add_plugin(OxidizedNavigationPlugin)
-        // consts manually tweaked
-        .insert_resource(NavMeshSettings {
-            cell_width: CELL_WIDTH,
-            cell_height: 0.5 * CELL_WIDTH,
-            tile_width: 170,
-            world_half_extents: 250.0,
-            world_bottom_bound: -20.0,
-            max_traversable_slope_radians: (40.0_f32 - 0.1).to_radians(),
-            walkable_height: 25,
-            walkable_radius: 4,
-            step_height: 3,
-            min_region_area: 30,
-            merge_region_area: 500,
-            max_contour_simplification_error: 1.3,
-            max_edge_length: 100,
-        })
+        .insert_resource(nav_mesh_settings(None))
         .add_system(
             query_mesh
                 .before(GeneralMovementSystemSet)
@@ -47,6 +35,29 @@ pub fn navigation_plugin(app: &mut App) {
         );
 }
 
+/// The navmesh covers the world between `-world_half_extents` and `world_half_extents` horizontally and
+/// everything above `world_bottom_bound`. Streamed levels are covered up to their
+/// [`StreamingSettings::bounds_in_cells`], all others up to a fixed distance.
+pub fn nav_mesh_settings(streaming: Option<&StreamingSettings>) -> NavMeshSettings {
+    // consts manually tweaked
+    NavMeshSettings {
+        cell_width: CELL_WIDTH,
+        cell_height: 0.5 * CELL_WIDTH,
+        tile_width: 170,
+        world_half_extents: streaming
+            .map_or(DEFAULT_WORLD_HALF_EXTENTS, StreamingSettings::half_extents),
+        world_bottom_bound: -20.0,
+        max_traversable_slope_radians: (40.0_f32 - 0.1).to_radians(),
+        walkable_height: 25,
+        walkable_radius: 4,
+        step_height: 3,
+        min_region_area: 30,
+        merge_region_area: 500,
+        max_contour_simplification_error: 1.3,
+        max_edge_length: 100,
+    }
+}
+
 #[derive(Debug, Component, Clone, PartialEq, Default, Reflect, Serialize, Deserialize)]
 #[reflect(Component, Serialize, Deserialize)]
 pub struct Follower;
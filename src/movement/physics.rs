@@ -1,3 +1,4 @@
+use crate::level_instantiation::streaming::StreamedCollider;
 use crate::util::trait_extension::MeshExt;
 use crate::GameState;
 use anyhow::{Context, Result};
@@ -39,9 +40,11 @@ pub fn read_colliders(
                     Collider::from_bevy_mesh(collider_mesh, &ComputedColliderShape::TriMesh)
                         .context("Failed to create collider from mesh")?;
 
-                commands
-                    .entity(collider_entity)
-                    .insert((rapier_collider, NavMeshAffector::default()));
+                commands.entity(collider_entity).insert((
+                    StreamedCollider(rapier_collider.clone()),
+                    rapier_collider,
+                    NavMeshAffector::default(),
+                ));
             }
         }
     }
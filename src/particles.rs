@@ -1,5 +1,4 @@
 use crate::file_system_interaction::config::GameConfig;
-use crate::level_instantiation::spawning::objects::player;
 use crate::movement::general_movement::Grounded;
 use crate::particles::init::init_effects;
 use crate::util::trait_extension::{F32Ext, Vec3Ext};
@@ -23,11 +22,11 @@ pub fn particle_plugin(app: &mut App) {
 struct SprintingParticle;
 
 fn play_sprinting_effect(
-    with_player: Query<(&Transform, &Grounded, &Velocity), Without<SprintingParticle>>,
+    with_player: Query<(&Transform, &Grounded, &Velocity, &Collider), Without<SprintingParticle>>,
     mut with_particle: Query<(&mut Transform, &mut ParticleEffect), With<SprintingParticle>>,
     config: Res<GameConfig>,
 ) {
-    for (player_transform, grounded, velocity) in with_player.iter() {
+    for (player_transform, grounded, velocity, collider) in with_player.iter() {
         let horizontal_speed_squared = velocity
             .linvel
             .split(player_transform.up())
@@ -36,8 +35,9 @@ fn play_sprinting_effect(
         for (mut particle_transform, mut effect) in with_particle.iter_mut() {
             let threshold = config.player.sprint_effect_speed_threshold;
             if grounded.0 && horizontal_speed_squared > threshold.squared() {
-                let translation = player_transform.translation
-                    - player_transform.up() * (player::HEIGHT / 2. + player::RADIUS);
+                // The bottom of the collider, i.e. the feet
+                let bottom = collider.raw.compute_local_aabb().mins.y;
+                let translation = player_transform.translation + player_transform.up() * bottom;
                 *particle_transform = player_transform.with_translation(translation);
                 effect.maybe_spawner().unwrap().set_active(true);
             } else {
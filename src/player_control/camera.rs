@@ -37,6 +37,48 @@ impl Default for IngameCamera {
     }
 }
 
+/// The parts of an [`IngameCamera`] and its [`Rig`] that the player controls. Stored in saves so that the camera
+/// looks the same after loading.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CameraState {
+    pub kind: IngameCameraKind,
+    pub desired_distance: f32,
+    pub yaw_degrees: f32,
+    pub pitch_degrees: f32,
+    /// `None` if the rig has no [`Arm`], e.g. in first person.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arm_offset: Option<Vec3>,
+}
+
+impl CameraState {
+    pub fn read(camera: &IngameCamera, rig: &Rig) -> Self {
+        let (yaw_degrees, pitch_degrees) = rig
+            .try_driver::<YawPitch>()
+            .map(|yaw_pitch| (yaw_pitch.yaw_degrees, yaw_pitch.pitch_degrees))
+            .unwrap_or_default();
+        Self {
+            kind: camera.kind.clone(),
+            desired_distance: camera.desired_distance,
+            yaw_degrees,
+            pitch_degrees,
+            arm_offset: rig.try_driver::<Arm>().map(|arm| arm.offset),
+        }
+    }
+
+    /// Drivers missing for [`CameraState::kind`] are added by [`update_drivers`] on the next update.
+    pub fn apply(&self, camera: &mut IngameCamera, rig: &mut Rig) {
+        camera.kind = self.kind.clone();
+        camera.desired_distance = self.desired_distance;
+        if let Some(yaw_pitch) = rig.try_driver_mut::<YawPitch>() {
+            yaw_pitch.yaw_degrees = self.yaw_degrees;
+            yaw_pitch.pitch_degrees = self.pitch_degrees;
+        }
+        if let Some(arm_offset) = self.arm_offset && let Some(arm) = rig.try_driver_mut::<Arm>() {
+            arm.offset = arm_offset;
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Reflect, FromReflect, Serialize, Deserialize, Default)]
 #[reflect(Serialize, Deserialize)]
 pub enum IngameCameraKind {
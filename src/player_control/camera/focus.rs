@@ -1,7 +1,8 @@
+use crate::level_instantiation::spawning::PersistentIdLookup;
 use crate::player_control::camera::IngameCamera;
 use crate::player_control::player_embodiment::Player;
 use crate::world_interaction::dialog::CurrentDialog;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use bevy::prelude::*;
 use bevy_mod_sysfail::macros::*;
 
@@ -11,13 +12,16 @@ pub fn set_camera_focus(
     current_dialog: Option<Res<CurrentDialog>>,
     player_query: Query<&Transform, With<Player>>,
     non_player_query: Query<&GlobalTransform, Without<Player>>,
+    lookup: Res<PersistentIdLookup>,
 ) -> Result<()> {
     for mut camera in camera_query.iter_mut() {
         for player_transform in player_query.iter() {
             if let Some(ref active_dialogue) = current_dialog {
-                let dialog_target_transform = non_player_query
-                    .get(active_dialogue.source)?
-                    .compute_transform();
+                let dialog_target = lookup
+                    .get(active_dialogue.source)
+                    .context("Failed to find source of current dialog")?;
+                let dialog_target_transform =
+                    non_player_query.get(dialog_target)?.compute_transform();
                 camera.secondary_target = Some(dialog_target_transform);
             } else {
                 camera.secondary_target = None;
@@ -1,5 +1,6 @@
 use crate::file_system_interaction::audio::AudioHandles;
 use crate::file_system_interaction::config::GameConfig;
+use crate::level_instantiation::spawning::PersistentIdLookup;
 use crate::movement::general_movement::{GeneralMovementSystemSet, Grounded, Jumping, Walking};
 use crate::player_control::actions::{DualAxisDataExt, PlayerAction};
 use crate::player_control::camera::{CameraUpdateSystemSet, IngameCamera, IngameCameraKind};
@@ -149,10 +150,14 @@ fn rotate_to_speaker(
     without_player: Query<&Transform, Without<Player>>,
     current_dialog: Res<CurrentDialog>,
     config: Res<GameConfig>,
+    lookup: Res<PersistentIdLookup>,
 ) {
     #[cfg(feature = "tracing")]
     let _span = info_span!("rotate_to_speaker").entered();
-    let Ok(speaker_transform) = without_player.get(current_dialog.source) else {
+    let Some(speaker) = lookup.get(current_dialog.source) else {
+        return;
+    };
+    let Ok(speaker_transform) = without_player.get(speaker) else {
          return;
     };
     let dt = time.delta_seconds();
@@ -1,5 +1,5 @@
 #![allow(clippy::extra_unused_type_parameters)]
-use crate::file_system_interaction::asset_loading::TextureAssets;
+use crate::file_system_interaction::asset_loading::{keys, NamedAssets};
 use crate::GameState;
 use anyhow::{Context, Result};
 use bevy::asset::HandleId;
@@ -39,13 +39,13 @@ fn setup_shader(
     mut commands: Commands,
     mut glow_materials: ResMut<Assets<GlowyMaterial>>,
     mut skydome_materials: ResMut<Assets<SkydomeMaterial>>,
-    texture_assets: Res<TextureAssets>,
+    assets: Res<NamedAssets>,
 ) {
     let glowy = glow_materials.add(GlowyMaterial {
-        env_texture: texture_assets.glowy_interior.clone(),
+        env_texture: assets.texture(keys::GLOWY_INTERIOR_TEXTURE),
     });
     let skydome = skydome_materials.add(SkydomeMaterial {
-        env_texture: texture_assets.sky.clone(),
+        env_texture: assets.texture(keys::SKY_TEXTURE),
     });
 
     commands.insert_resource(Materials {
@@ -157,6 +157,7 @@ impl F32Ext for f32 {
 pub trait TransformExt: Copy {
     fn horizontally_looking_at(self, target: Vec3, up: Vec3) -> Transform;
     fn lerp(self, other: Transform, ratio: f32) -> Transform;
+    fn is_approx_eq(self, other: Transform) -> bool;
 }
 
 impl TransformExt for Transform {
@@ -177,4 +178,11 @@ impl TransformExt for Transform {
             scale,
         }
     }
+
+    fn is_approx_eq(self, other: Transform) -> bool {
+        const EPSILON: f32 = 1e-4;
+        self.translation.abs_diff_eq(other.translation, EPSILON)
+            && self.rotation.abs_diff_eq(other.rotation, EPSILON)
+            && self.scale.abs_diff_eq(other.scale, EPSILON)
+    }
 }
@@ -1,10 +1,12 @@
 pub mod condition;
 pub mod dialog;
 pub mod interactions_ui;
+pub mod objective;
 
 use crate::world_interaction::condition::condition_plugin;
 use crate::world_interaction::dialog::dialog_plugin;
 use crate::world_interaction::interactions_ui::interactions_ui_plugin;
+use crate::world_interaction::objective::objective_plugin;
 use bevy::prelude::*;
 use seldom_fn_plugin::FnPluginExt;
 
@@ -12,8 +14,10 @@ use seldom_fn_plugin::FnPluginExt;
 /// - [`condition_plugin`] handles trackers of player actions such as chosen dialog options
 /// - [`dialog_plugin`] handles dialog trees
 /// - [`interactions_ui_plugin`] handles the UI for interacting with an object in front of the player.
+/// - [`objective_plugin`] tracks what the player is currently supposed to do.
 pub fn world_interaction_plugin(app: &mut App) {
     app.fn_plugin(condition_plugin)
         .fn_plugin(dialog_plugin)
-        .fn_plugin(interactions_ui_plugin);
+        .fn_plugin(interactions_ui_plugin)
+        .fn_plugin(objective_plugin);
 }
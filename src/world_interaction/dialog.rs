@@ -16,7 +16,6 @@ use bevy_egui::{egui, EguiContexts, EguiPlugin};
 use bevy_mod_sysfail::macros::*;
 use leafwing_input_manager::prelude::ActionState;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
 use unicode_segmentation::UnicodeSegmentation;
 
 mod resources;
@@ -28,6 +27,11 @@ pub fn dialog_plugin(app: &mut App) {
         .add_systems((set_current_dialog, show_dialog).in_set(OnUpdate(GameState::Playing)));
 }
 
+/// Returns the key of a dialog in [`DialogAssets::dialogs`], e.g. `dialogs/follower.dlg.ron` for `follower`.
+pub fn get_dialog_asset_path(dialog: &DialogId) -> String {
+    format!("dialogs/{}.dlg.ron", dialog.0)
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Component, Serialize, Deserialize, Default)]
 pub struct DialogTarget {
     pub dialog_id: DialogId,
@@ -43,17 +47,7 @@ fn set_current_dialog(
     mut actions_frozen: ResMut<ActionsFrozen>,
 ) -> Result<()> {
     for dialog_event in dialog_events.iter() {
-        let path = Path::new("dialogs")
-            .join(&dialog_event.dialog.0.clone())
-            .with_extension("dlg.ron")
-            .to_str()
-            .with_context(|| {
-                format!(
-                    "Failed to convert dialog path to string for dialog: {:?}",
-                    dialog_event.dialog
-                )
-            })?
-            .to_owned();
+        let path = get_dialog_asset_path(&dialog_event.dialog);
         let dialog_handle = match dialog_handles.dialogs.get(&path) {
             Some(handle) => handle,
             None => {
@@ -1,3 +1,4 @@
+use crate::level_instantiation::spawning::PersistentId;
 use crate::world_interaction::condition::{ActiveConditions, ConditionId};
 use anyhow::{Context, Result};
 use bevy::prelude::*;
@@ -10,13 +11,13 @@ use serde::{Deserialize, Serialize};
 #[reflect(Serialize, Deserialize)]
 pub struct DialogEvent {
     pub dialog: DialogId,
-    pub source: Entity,
+    pub source: PersistentId,
     pub page: Option<PageId>,
 }
 
 #[derive(Debug, Clone, PartialEq, Resource, Serialize, Deserialize)]
 pub struct CurrentDialog {
-    pub source: Entity,
+    pub source: PersistentId,
     pub id: DialogId,
     pub dialog: Dialog,
     pub current_page: PageId,
@@ -1,3 +1,4 @@
+use crate::level_instantiation::spawning::{PersistentId, PersistentIdLookup};
 use crate::player_control::actions::PlayerAction;
 use crate::player_control::camera::{IngameCamera, IngameCameraKind};
 use crate::player_control::player_embodiment::Player;
@@ -32,17 +33,19 @@ pub fn interactions_ui_plugin(app: &mut App) {
 
 #[derive(Resource, Debug)]
 pub struct InteractionUi {
-    source: Entity,
+    source: PersistentId,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Resource, Reflect, Serialize, Deserialize, Default)]
 #[reflect(Resource, Serialize, Deserialize)]
-pub struct InteractionOpportunities(pub HashSet<Entity>);
+pub struct InteractionOpportunities(pub HashSet<PersistentId>);
 
 fn update_interaction_opportunities(
     mut collision_events: EventReader<CollisionEvent>,
     player_query: Query<Entity, With<Player>>,
     parent_query: Query<&Parent>,
+    // Other sensors like checkpoints and transitions also report collisions, but cannot be talked to
+    id_query: Query<&PersistentId, With<DialogTarget>>,
     mut interaction_opportunities: ResMut<InteractionOpportunities>,
 ) {
     for event in collision_events.iter() {
@@ -53,10 +56,13 @@ fn update_interaction_opportunities(
                 Some((dialog_source, dialog_target)) => (dialog_source, dialog_target),
                 None => continue,
             };
+        let Ok(target_id) = id_query.get(target_entity) else {
+            continue;
+        };
         if ongoing {
-            interaction_opportunities.0.insert(target_entity);
+            interaction_opportunities.0.insert(*target_id);
         } else {
-            interaction_opportunities.0.remove(&target_entity);
+            interaction_opportunities.0.remove(target_id);
         }
     }
 }
@@ -69,11 +75,16 @@ fn update_interaction_ui(
     player_query: Query<&Transform, (With<Player>, Without<IngameCamera>)>,
     interaction_opportunities: Res<InteractionOpportunities>,
     camera_query: Query<(&IngameCamera, &Transform), Without<Player>>,
+    lookup: Res<PersistentIdLookup>,
 ) -> Result<()> {
     let mut valid_target = None;
-    for entity in interaction_opportunities.0.iter() {
+    for id in interaction_opportunities.0.iter() {
+        // The target might have been despawned while the player was standing next to it
+        let Some(entity) = lookup.get(*id) else {
+            continue;
+        };
         let target_transform = non_player_query
-            .get(*entity)
+            .get(entity)
             .context("Failed to get transform of interaction target")?;
         for player_transform in player_query.iter() {
             for (camera, camera_transform) in camera_query.iter() {
@@ -84,7 +95,7 @@ fn update_interaction_ui(
                     camera,
                 );
                 if is_facing_target {
-                    valid_target = Some(*entity);
+                    valid_target = Some(*id);
                     break;
                 }
             }
@@ -160,6 +171,7 @@ fn display_interaction_prompt(
     actions: Query<&ActionState<PlayerAction>>,
     primary_windows: Query<&Window, With<PrimaryWindow>>,
     dialog_target_query: Query<&DialogTarget>,
+    lookup: Res<PersistentIdLookup>,
 ) -> Result<()> {
     for actions in actions.iter() {
         let window = primary_windows
@@ -174,7 +186,10 @@ fn display_interaction_prompt(
                 ui.label("E: Talk");
             });
         if actions.just_pressed(PlayerAction::Interact) {
-            if let Ok(dialog_target) = dialog_target_query.get(interaction_ui.source) {
+            let dialog_target = lookup
+                .get(interaction_ui.source)
+                .and_then(|entity| dialog_target_query.get(entity).ok());
+            if let Some(dialog_target) = dialog_target {
                 dialog_event_writer.send(DialogEvent {
                     source: interaction_ui.source,
                     dialog: dialog_target.dialog_id.clone(),
@@ -185,3 +200,43 @@ fn display_interaction_prompt(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_rapier3d::rapier::geometry::CollisionEventFlags;
+
+    fn collide(world: &mut World, a: Entity, b: Entity) {
+        world.send_event(CollisionEvent::Started(a, b, CollisionEventFlags::SENSOR));
+        let mut system = IntoSystem::into_system(update_interaction_opportunities);
+        system.initialize(world);
+        system.run((), world);
+    }
+
+    fn world_with_player() -> (World, Entity) {
+        let mut world = World::new();
+        world.init_resource::<Events<CollisionEvent>>();
+        world.init_resource::<InteractionOpportunities>();
+        let player = world.spawn(Player).id();
+        (world, player)
+    }
+
+    #[test]
+    fn dialog_targets_are_interaction_opportunities() {
+        let (mut world, player) = world_with_player();
+        let npc = world.spawn((PersistentId(1), DialogTarget::default())).id();
+        collide(&mut world, player, npc);
+        assert_eq!(
+            world.resource::<InteractionOpportunities>().0,
+            [PersistentId(1)].into_iter().collect()
+        );
+    }
+
+    #[test]
+    fn checkpoints_are_not_interaction_opportunities() {
+        let (mut world, player) = world_with_player();
+        let checkpoint = world.spawn(PersistentId(2)).id();
+        collide(&mut world, checkpoint, player);
+        assert!(world.resource::<InteractionOpportunities>().0.is_empty());
+    }
+}
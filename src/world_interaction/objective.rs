@@ -0,0 +1,13 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub fn objective_plugin(app: &mut App) {
+    app.register_type::<CurrentObjective>()
+        .init_resource::<CurrentObjective>();
+}
+
+/// What the player is currently supposed to do, in words meant for the player.
+/// Shown in the save browser and carried over in saves.
+#[derive(Debug, Clone, Eq, PartialEq, Resource, Reflect, Serialize, Deserialize, Default)]
+#[reflect(Resource, Serialize, Deserialize)]
+pub struct CurrentObjective(pub Option<String>);